@@ -0,0 +1,84 @@
+//! EIP-4844 blob transactions, which ethers' [`TypedTransaction`] can't express.
+//!
+//! They are executed through the same `*_with_overrides` methods as any other transaction, see
+//! [`CallRequest`].
+//!
+//! [`TypedTransaction`]: ethers::types::transaction::eip2718::TypedTransaction
+//! [`CallRequest`]: crate::overrides::CallRequest
+
+use ethers::types::{
+    Bytes as EthersBytes, Eip1559TransactionRequest, H256 as EthersH256, U256 as EthersU256,
+};
+use reth_primitives::{
+    constants::eip4844::MAINNET_KZG_TRUSTED_SETUP,
+    eip4844::kzg_to_versioned_hash,
+    kzg::{Blob, Error as KzgError, KzgCommitment, KzgProof},
+};
+
+use crate::type_conversions::ToEthers;
+
+/// The blobs of an EIP-4844 transaction with their KZG commitments and proofs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobSidecar {
+    pub blobs: Vec<EthersBytes>,
+    pub commitments: Vec<EthersBytes>,
+    pub proofs: Vec<EthersBytes>,
+}
+
+impl BlobSidecar {
+    /// Computes the commitments and proofs of the blobs with the mainnet trusted setup.
+    pub fn from_blobs(blobs: Vec<EthersBytes>) -> Result<Self, KzgError> {
+        let settings = &*MAINNET_KZG_TRUSTED_SETUP;
+        let mut commitments = Vec::with_capacity(blobs.len());
+        let mut proofs = Vec::with_capacity(blobs.len());
+
+        for blob in &blobs {
+            let blob = Blob::from_bytes(blob)?;
+            let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings)?;
+            let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment.to_bytes(), settings)?;
+            commitments.push(EthersBytes::from(commitment.to_bytes().to_vec()));
+            proofs.push(EthersBytes::from(proof.to_bytes().to_vec()));
+        }
+
+        Ok(Self { blobs, commitments, proofs })
+    }
+
+    /// Returns the versioned hashes of the commitments, which the transaction refers to.
+    pub fn versioned_hashes(&self) -> Result<Vec<EthersH256>, KzgError> {
+        self.commitments
+            .iter()
+            .map(|commitment| -> Result<EthersH256, KzgError> {
+                let commitment = KzgCommitment::from_bytes(commitment)?;
+                Ok(kzg_to_versioned_hash(commitment).into_ethers())
+            })
+            .collect()
+    }
+}
+
+/// An EIP-4844 transaction request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Eip4844TransactionRequest {
+    /// The fee market fields of the transaction, blob transactions can't create contracts.
+    pub tx: Eip1559TransactionRequest,
+    pub max_fee_per_blob_gas: Option<EthersU256>,
+    pub blob_versioned_hashes: Vec<EthersH256>,
+    /// The blobs, only required when the transaction is sent.
+    pub sidecar: Option<BlobSidecar>,
+}
+
+impl Eip4844TransactionRequest {
+    /// Creates a request carrying the given blobs, deriving their versioned hashes.
+    pub fn with_blobs(
+        tx: Eip1559TransactionRequest,
+        max_fee_per_blob_gas: EthersU256,
+        blobs: Vec<EthersBytes>,
+    ) -> Result<Self, KzgError> {
+        let sidecar = BlobSidecar::from_blobs(blobs)?;
+        Ok(Self {
+            tx,
+            max_fee_per_blob_gas: Some(max_fee_per_blob_gas),
+            blob_versioned_hashes: sidecar.versioned_hashes()?,
+            sidecar: Some(sidecar),
+        })
+    }
+}
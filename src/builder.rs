@@ -0,0 +1,291 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use ethers::{
+    abi::Abi,
+    providers::{JsonRpcClient, Middleware, Provider, DEFAULT_LOCAL_POLL_INTERVAL},
+};
+use reth_blockchain_tree::BlockchainTreeConfig;
+use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, ChainSpec};
+use reth_rpc::eth::{
+    cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, EthFilterConfig,
+    FeeHistoryCacheConfig,
+};
+use tokio::runtime::Handle;
+
+use crate::{
+    canon_state::DEFAULT_POLL_INTERVAL, init::InitError, pool::TxSubmission,
+    transport::RethTransport, RethMiddleware,
+};
+
+/// Default number of concurrent tracing requests, see [`BlockingTaskGuard`].
+///
+/// [`BlockingTaskGuard`]: reth_tasks::pool::BlockingTaskGuard
+pub const DEFAULT_MAX_TRACING_REQUESTS: u32 = 10;
+
+/// Builder for a [`RethMiddleware`] that exposes the configuration of every component of the
+/// in-process node.
+///
+/// All values default to the ones reth uses for its own RPC server.
+#[derive(Debug, Clone)]
+pub struct RethMiddlewareBuilder {
+    /// The reth datadir, containing the `db` directory.
+    pub(crate) datadir: PathBuf,
+    /// Directory of the static files, defaults to `<datadir>/static_files`.
+    pub(crate) static_files_path: Option<PathBuf>,
+    /// The chain of the database, detected from its genesis header if unset.
+    pub(crate) chain: Option<Arc<ChainSpec>>,
+    /// Whether the database is opened read-only, see [`Self::read_only`].
+    pub(crate) read_only: bool,
+    /// Interval at which the database is polled for new blocks, see [`Self::follow`].
+    pub(crate) follow_interval: Option<Duration>,
+    /// Where transactions are submitted to, see [`Self::tx_submission`].
+    pub(crate) tx_submission: TxSubmission,
+    /// Custom errors revert data is decoded with, see [`Self::error_abi`].
+    pub(crate) error_abi: Option<Arc<Abi>>,
+    pub(crate) state_cache_config: EthStateCacheConfig,
+    pub(crate) gas_oracle_config: GasPriceOracleConfig,
+    pub(crate) fee_history_cache_config: FeeHistoryCacheConfig,
+    pub(crate) filter_config: EthFilterConfig,
+    pub(crate) tree_config: BlockchainTreeConfig,
+    pub(crate) max_tracing_requests: u32,
+    pub(crate) gas_cap: u64,
+}
+
+impl RethMiddlewareBuilder {
+    /// Creates a new builder for the reth datadir at `datadir`.
+    ///
+    /// Unless a chain is set with [`Self::chain_spec`], it is detected from the genesis header
+    /// stored in the database, which only works for the chains built into reth.
+    pub fn new<P: AsRef<Path>>(datadir: P) -> Self {
+        Self {
+            datadir: datadir.as_ref().to_path_buf(),
+            static_files_path: None,
+            chain: None,
+            read_only: true,
+            follow_interval: None,
+            tx_submission: TxSubmission::default(),
+            error_abi: None,
+            state_cache_config: EthStateCacheConfig::default(),
+            gas_oracle_config: GasPriceOracleConfig::default(),
+            fee_history_cache_config: FeeHistoryCacheConfig::default(),
+            filter_config: EthFilterConfig::default(),
+            tree_config: BlockchainTreeConfig::default(),
+            max_tracing_requests: DEFAULT_MAX_TRACING_REQUESTS,
+            gas_cap: ETHEREUM_BLOCK_GAS_LIMIT,
+        }
+    }
+
+    /// Sets the [`ChainSpec`] of the database, see [`crate::chain`] for ways to build one.
+    pub fn chain_spec(mut self, chain: Arc<ChainSpec>) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    /// Sets whether the database is opened read-only, which is the default.
+    ///
+    /// A read-only database can safely be opened next to a running reth node, any number of
+    /// times. Only disable this for a datadir no other process is using.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Follows the canonical chain of the reth node writing to the database, polling it every
+    /// [`DEFAULT_POLL_INTERVAL`].
+    ///
+    /// New blocks become visible to all queries and are announced to the subscribers of
+    /// [`RethMiddleware::subscribe_canonical_state`].
+    ///
+    /// Polling stops once the middleware and all its clones are dropped.
+    pub fn follow(self) -> Self {
+        self.follow_interval(DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Follows the canonical chain like [`Self::follow`], polling at the given interval.
+    pub fn follow_interval(mut self, interval: Duration) -> Self {
+        self.follow_interval = Some(interval);
+        self
+    }
+
+    /// Sets where [`Middleware::send_raw_transaction`] and [`Middleware::send_transaction`]
+    /// submit transactions to.
+    ///
+    /// By default they are sent to the inner provider only. With [`TxSubmission::Local`] they are
+    /// validated against the database and inserted into the embedded pool instead, which makes
+    /// them visible to the pool, filter and `txpool_*` methods of the middleware.
+    pub fn tx_submission(mut self, tx_submission: TxSubmission) -> Self {
+        self.tx_submission = tx_submission;
+        self
+    }
+
+    /// Registers an ABI whose custom errors are decoded when `call` or `estimate_gas` revert.
+    ///
+    /// `Error(string)` and `Panic(uint256)` are always decoded, see
+    /// [`RevertReason`](crate::revert::RevertReason).
+    pub fn error_abi(mut self, abi: Abi) -> Self {
+        self.error_abi = Some(Arc::new(abi));
+        self
+    }
+
+    /// Sets the directory of the static files.
+    pub fn static_files_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.static_files_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the whole [`EthStateCacheConfig`].
+    pub fn state_cache_config(mut self, config: EthStateCacheConfig) -> Self {
+        self.state_cache_config = config;
+        self
+    }
+
+    /// Sets the maximum number of blocks kept in the state cache.
+    pub fn max_cached_blocks(mut self, max_blocks: u32) -> Self {
+        self.state_cache_config.max_blocks = max_blocks;
+        self
+    }
+
+    /// Sets the maximum number of receipts kept in the state cache.
+    pub fn max_cached_receipts(mut self, max_receipts: u32) -> Self {
+        self.state_cache_config.max_receipts = max_receipts;
+        self
+    }
+
+    /// Sets the maximum number of evm environments kept in the state cache.
+    pub fn max_cached_envs(mut self, max_envs: u32) -> Self {
+        self.state_cache_config.max_envs = max_envs;
+        self
+    }
+
+    /// Sets the maximum number of concurrent database requests of the state cache.
+    pub fn max_concurrent_db_requests(mut self, max_requests: usize) -> Self {
+        self.state_cache_config.max_concurrent_db_requests = max_requests;
+        self
+    }
+
+    /// Sets the whole [`GasPriceOracleConfig`].
+    pub fn gas_oracle_config(mut self, config: GasPriceOracleConfig) -> Self {
+        self.gas_oracle_config = config;
+        self
+    }
+
+    /// Sets the number of recent blocks the gas price oracle samples.
+    pub fn gas_oracle_blocks(mut self, blocks: u32) -> Self {
+        self.gas_oracle_config.blocks = blocks;
+        self
+    }
+
+    /// Sets the percentile of the sampled gas prices the oracle suggests.
+    pub fn gas_oracle_percentile(mut self, percentile: u32) -> Self {
+        self.gas_oracle_config.percentile = percentile;
+        self
+    }
+
+    /// Sets the [`FeeHistoryCacheConfig`].
+    pub fn fee_history_cache_config(mut self, config: FeeHistoryCacheConfig) -> Self {
+        self.fee_history_cache_config = config;
+        self
+    }
+
+    /// Sets the whole [`EthFilterConfig`].
+    pub fn filter_config(mut self, config: EthFilterConfig) -> Self {
+        self.filter_config = config;
+        self
+    }
+
+    /// Sets the duration after which an unpolled filter is removed.
+    pub fn stale_filter_ttl(mut self, ttl: Duration) -> Self {
+        self.filter_config = self.filter_config.stale_filter_ttl(ttl);
+        self
+    }
+
+    /// Sets the maximum number of blocks a single log query may span.
+    pub fn max_blocks_per_filter(mut self, max_blocks: u64) -> Self {
+        self.filter_config = self.filter_config.max_blocks_per_filter(max_blocks);
+        self
+    }
+
+    /// Sets the maximum number of logs a single log query may return.
+    pub fn max_logs_per_response(mut self, max_logs: usize) -> Self {
+        self.filter_config = self.filter_config.max_logs_per_response(max_logs);
+        self
+    }
+
+    /// Sets the [`BlockchainTreeConfig`].
+    pub fn tree_config(mut self, config: BlockchainTreeConfig) -> Self {
+        self.tree_config = config;
+        self
+    }
+
+    /// Sets the maximum number of tracing requests that may run concurrently.
+    pub fn max_tracing_requests(mut self, max_requests: u32) -> Self {
+        self.max_tracing_requests = max_requests;
+        self
+    }
+
+    /// Sets the gas cap applied to `eth_call`, `eth_estimateGas` and call tracing.
+    pub fn gas_cap(mut self, gas_cap: u64) -> Self {
+        self.gas_cap = gas_cap;
+        self
+    }
+
+    /// Returns the directory of the static files.
+    pub fn static_files_dir(&self) -> PathBuf {
+        self.static_files_path.clone().unwrap_or_else(|| self.datadir.join("static_files"))
+    }
+
+    /// Opens the database and spawns all components on the given runtime.
+    pub fn build<M: Middleware>(
+        self,
+        inner: M,
+        handle: Handle,
+    ) -> Result<RethMiddleware<M>, InitError> {
+        let (
+            reth_api,
+            reth_filter,
+            reth_trace,
+            reth_debug,
+            reth_txpool,
+            reth_bundle,
+            canon_state,
+        ) = RethMiddleware::<M>::try_new(&self, handle)?;
+        let max_blocks_per_filter =
+            self.filter_config.max_blocks_per_filter.unwrap_or(u64::MAX).max(1);
+        Ok(RethMiddleware {
+            inner,
+            reth_api,
+            reth_filter,
+            reth_trace,
+            reth_debug,
+            reth_txpool,
+            reth_bundle,
+            canon_state,
+            tx_submission: self.tx_submission,
+            error_abi: self.error_abi.clone(),
+            serves_transport: false,
+            max_blocks_per_filter,
+        })
+    }
+
+    /// Builds the middleware over a [`Provider`] whose transport wraps `transport` in a
+    /// [`RethTransport`], so the filters and subscriptions behind [`Middleware::watch_blocks`]
+    /// and friends are served by the embedded node, see [`crate::transport`].
+    pub fn build_with_transport<P: JsonRpcClient + 'static>(
+        self,
+        transport: P,
+        handle: Handle,
+    ) -> Result<RethMiddleware<Provider<RethTransport<P>>>, InitError> {
+        let transport = RethTransport::new(transport);
+        let node = transport.node();
+        let provider = Provider::new(transport).interval(DEFAULT_LOCAL_POLL_INTERVAL);
+
+        let mut middleware = self.build(provider, handle)?;
+        node.connect(middleware.reth_api.clone(), middleware.reth_filter.clone());
+        middleware.serves_transport = true;
+        Ok(middleware)
+    }
+}
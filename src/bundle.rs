@@ -0,0 +1,260 @@
+//! Sequences of transactions executed on top of each other without being committed.
+
+use ethers::{
+    providers::{spoof, Middleware},
+    types::{
+        transaction::eip2718::TypedTransaction, Address as EthersAddress,
+        BlockId as EthersBlockId, BlockNumber as EthersBlockNumber, Bytes as EthersBytes,
+        Log as EthersLog, H256 as EthersH256, U256 as EthersU256, U64 as EthersU64,
+    },
+};
+use reth_rpc_api::EthApiServer;
+use reth_rpc_types::{
+    trace::geth::{
+        CallFrame, GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType,
+        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
+    },
+    Bundle, EthCallBundle, StateContext,
+};
+use serde_json::json;
+
+use crate::{
+    overrides::BlockOverrides,
+    type_conversions::{ConversionError, ToEthers, ToReth, TryToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// The outcome of a transaction of a bundle simulated by [`RethMiddleware::simulate_bundle`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulatedTransaction {
+    /// The return data, or the revert data if the transaction reverted.
+    pub output: EthersBytes,
+    pub gas_used: EthersU256,
+    /// The logs emitted by all calls that didn't revert.
+    ///
+    /// Logs of a call come before the logs of its subcalls, the tracer doesn't record how they
+    /// interleave.
+    pub logs: Vec<EthersLog>,
+    /// Why the transaction failed, e.g. `execution reverted` or `out of gas`.
+    pub error: Option<String>,
+    /// The decoded `Error(string)` the transaction reverted with.
+    pub revert_reason: Option<String>,
+}
+
+impl SimulatedTransaction {
+    /// Returns `true` if the transaction didn't fail.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl From<CallFrame> for SimulatedTransaction {
+    fn from(frame: CallFrame) -> Self {
+        let mut logs = Vec::new();
+        collect_logs(&frame, &mut logs);
+
+        Self {
+            output: frame.output.unwrap_or_default().into_ethers(),
+            gas_used: frame.gas_used.into_ethers(),
+            logs,
+            error: frame.error,
+            revert_reason: frame.revert_reason,
+        }
+    }
+}
+
+/// Signed transactions to execute on top of a block, the parameters of Flashbots'
+/// `eth_callBundle`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallBundle {
+    /// The signed raw transactions, executed in order.
+    pub txs: Vec<EthersBytes>,
+    /// The number of the block the bundle targets, the transactions are executed in its context.
+    pub block_number: EthersU64,
+    /// The block whose state the bundle is executed on top of.
+    pub state_block_number: EthersBlockNumber,
+    /// Overrides the timestamp of the targeted block.
+    pub timestamp: Option<u64>,
+}
+
+impl CallBundle {
+    /// A bundle targeting the given block on top of the latest state.
+    pub fn new<T: Into<EthersU64>>(txs: Vec<EthersBytes>, block_number: T) -> Self {
+        Self {
+            txs,
+            block_number: block_number.into(),
+            state_block_number: EthersBlockNumber::Latest,
+            timestamp: None,
+        }
+    }
+
+    pub fn state_block_number<T: Into<EthersBlockNumber>>(mut self, block: T) -> Self {
+        self.state_block_number = block.into();
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+}
+
+/// The outcome of a bundle executed by [`RethMiddleware::call_bundle`], shaped like the response
+/// of Flashbots' `eth_callBundle`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallBundleResponse {
+    pub bundle_hash: EthersH256,
+    /// The coinbase difference divided by the gas used by the bundle.
+    pub bundle_gas_price: EthersU256,
+    /// How much the balance of the coinbase grew, i.e. the gas fees and direct payments.
+    pub coinbase_diff: EthersU256,
+    /// The direct payments to the coinbase.
+    pub eth_sent_to_coinbase: EthersU256,
+    pub gas_fees: EthersU256,
+    pub results: Vec<CallBundleTransactionResult>,
+    pub state_block_number: u64,
+    pub total_gas_used: u64,
+}
+
+/// The outcome of a transaction of a bundle executed by [`RethMiddleware::call_bundle`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallBundleTransactionResult {
+    pub coinbase_diff: EthersU256,
+    pub eth_sent_to_coinbase: EthersU256,
+    pub from_address: EthersAddress,
+    pub gas_fees: EthersU256,
+    /// The effective gas price of the transaction.
+    pub gas_price: EthersU256,
+    pub gas_used: u64,
+    pub to_address: Option<EthersAddress>,
+    pub tx_hash: EthersH256,
+    /// The return data of the transaction if it succeeded.
+    pub value: Option<EthersBytes>,
+    /// The revert data of the transaction if it reverted.
+    pub revert: Option<EthersBytes>,
+}
+
+/// Collects the logs of the frame and its subcalls, skipping reverted calls.
+fn collect_logs(frame: &CallFrame, logs: &mut Vec<EthersLog>) {
+    if frame.error.is_some() {
+        return
+    }
+
+    logs.extend(frame.logs.iter().map(|log| EthersLog {
+        address: log.address.or(frame.to).unwrap_or_default().into_ethers(),
+        topics: log.topics.clone().unwrap_or_default().into_ethers(),
+        data: log.data.clone().unwrap_or_default().into_ethers(),
+        ..Default::default()
+    }));
+
+    for call in &frame.calls {
+        collect_logs(call, logs);
+    }
+}
+
+/// A bundle of the transactions, executed in the given order.
+fn bundle(
+    txs: Vec<TypedTransaction>,
+    block_overrides: Option<BlockOverrides>,
+) -> Result<Bundle, ConversionError> {
+    Ok(Bundle {
+        transactions: txs.try_into_reth()?,
+        block_override: block_overrides.map(|overrides| overrides.into_reth()),
+    })
+}
+
+/// Executes on top of the state after all transactions of the block.
+fn state_context(block: Option<EthersBlockId>) -> StateContext {
+    StateContext { block_number: block.into_reth(), transaction_index: None }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Executes the transactions in order, each on top of the state changes of the previous
+    /// ones, and returns their output or error, like `eth_callMany`.
+    pub async fn call_many(
+        &self,
+        txs: Vec<TypedTransaction>,
+        block: Option<EthersBlockId>,
+        state: Option<spoof::State>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Vec<Result<EthersBytes, String>>, RethMiddlewareError<M>> {
+        let bundle = bundle(txs, block_overrides)?;
+        let state_override = state.try_into_reth()?;
+        let responses = self
+            .reth_api
+            .call_many(bundle, Some(state_context(block)), state_override)
+            .await
+            .map_err(|err| self.map_revert(err))?;
+
+        Ok(responses
+            .into_iter()
+            .map(|response| match response.error {
+                Some(error) => Err(error),
+                None => Ok(response.value.unwrap_or_default().into_ethers()),
+            })
+            .collect())
+    }
+
+    /// Executes the transactions like [`RethMiddleware::call_many`], but reports the gas used,
+    /// logs and revert reason of each transaction as well.
+    pub async fn simulate_bundle(
+        &self,
+        txs: Vec<TypedTransaction>,
+        block: Option<EthersBlockId>,
+        state: Option<spoof::State>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Vec<SimulatedTransaction>, RethMiddlewareError<M>> {
+        let tracing_options = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::CallTracer,
+            )),
+            tracer_config: GethDebugTracerConfig(json!({ "withLog": true })),
+            ..Default::default()
+        };
+        let opts = GethDebugTracingCallOptions {
+            tracing_options,
+            state_overrides: state.try_into_reth()?,
+            block_overrides: None,
+        };
+
+        let traces = self
+            .reth_debug
+            .debug_trace_call_many(
+                vec![bundle(txs, block_overrides)?],
+                Some(state_context(block)),
+                Some(opts),
+            )
+            .await
+            .map_err(|err| self.map_revert(err))?;
+
+        traces
+            .into_iter()
+            .flatten()
+            .map(|trace| match trace {
+                GethTrace::CallTracer(frame) => Ok(frame.into()),
+                _ => Err(RethMiddlewareError::MissingTrace),
+            })
+            .collect()
+    }
+
+    /// Executes signed transactions on top of a block and reports the gas used, fees and
+    /// coinbase payments of each, like Flashbots' `eth_callBundle`.
+    pub async fn call_bundle(
+        &self,
+        bundle: CallBundle,
+    ) -> Result<CallBundleResponse, RethMiddlewareError<M>> {
+        let bundle = EthCallBundle {
+            txs: bundle.txs.into_reth(),
+            block_number: bundle.block_number.into_reth(),
+            state_block_number: bundle.state_block_number.into_reth(),
+            timestamp: bundle.timestamp,
+        };
+
+        let response =
+            self.reth_bundle.call_bundle(bundle).await.map_err(|err| self.map_revert(err))?;
+        Ok(response.into_ethers())
+    }
+}
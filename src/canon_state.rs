@@ -0,0 +1,384 @@
+//! Following the canonical chain of a running reth node.
+//!
+//! The node commits new blocks to the database it shares with the middleware, so following it
+//! only requires periodically opening a fresh read transaction and comparing the canonical hashes
+//! with the ones seen last.
+
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use ethers::{providers::Middleware, types::H256 as EthersH256};
+use futures::Stream;
+use reth_db::DatabaseEnv;
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    BlockHashReader, BlockNumReader, CanonChainTracker, HeaderProvider, ProviderError,
+    ProviderFactory,
+};
+use tokio::{
+    sync::{broadcast, oneshot},
+    time::MissedTickBehavior,
+};
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::{type_conversions::ToEthers, RethClient, RethMiddleware};
+
+/// Default interval at which the database is polled for new blocks.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A block of the canonical chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalBlock {
+    pub number: BlockNumber,
+    pub hash: EthersH256,
+}
+
+/// A change of the canonical chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanonStateEvent {
+    /// Blocks were appended to the canonical chain, oldest first.
+    Commit { new: Vec<CanonicalBlock> },
+    /// The `old` blocks were replaced by the `new` ones, both oldest first.
+    Reorg { old: Vec<CanonicalBlock>, new: Vec<CanonicalBlock> },
+}
+
+impl CanonStateEvent {
+    /// Returns the blocks that became canonical.
+    pub fn committed(&self) -> &[CanonicalBlock] {
+        match self {
+            CanonStateEvent::Commit { new } | CanonStateEvent::Reorg { new, .. } => new,
+        }
+    }
+
+    /// Returns the blocks that are no longer canonical.
+    pub fn reverted(&self) -> &[CanonicalBlock] {
+        match self {
+            CanonStateEvent::Commit { .. } => &[],
+            CanonStateEvent::Reorg { old, .. } => old,
+        }
+    }
+
+    /// Returns the new tip of the canonical chain, if any block became canonical.
+    pub fn tip(&self) -> Option<CanonicalBlock> {
+        self.committed().last().copied()
+    }
+}
+
+/// A stream of [`CanonStateEvent`]s, see [`RethMiddleware::subscribe_canonical_state`].
+///
+/// Events missed because the subscriber lagged behind are skipped.
+#[derive(Debug)]
+pub struct CanonStateStream {
+    inner: BroadcastStream<CanonStateEvent>,
+}
+
+impl Stream for CanonStateStream {
+    type Item = CanonStateEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => return Poll::Ready(Some(event)),
+                Poll::Ready(Some(Err(_lagged))) => continue,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The sender of the [`CanonStateEvent`]s, shared by all clones of a middleware.
+///
+/// Dropping the last clone stops the [`CanonStateFollower`] broadcasting the events.
+#[derive(Debug, Clone)]
+pub(crate) struct CanonStateSender {
+    sender: broadcast::Sender<CanonStateEvent>,
+    _shutdown: Arc<oneshot::Sender<()>>,
+}
+
+impl CanonStateSender {
+    /// Creates a sender buffering up to `capacity` events, and the signal that resolves once the
+    /// sender and all its clones are dropped.
+    pub(crate) fn new(capacity: usize) -> (Self, oneshot::Receiver<()>) {
+        let (sender, _receiver) = broadcast::channel(capacity);
+        let (shutdown, signal) = oneshot::channel();
+        (Self { sender, _shutdown: Arc::new(shutdown) }, signal)
+    }
+
+    /// Returns a sender of events that doesn't keep the follower running.
+    pub(crate) fn events(&self) -> broadcast::Sender<CanonStateEvent> {
+        self.sender.clone()
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<CanonStateEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Subscribes to new blocks and reorgs of the canonical chain.
+    ///
+    /// Events are only produced if the middleware was built with
+    /// [`RethMiddlewareBuilder::follow`](crate::builder::RethMiddlewareBuilder::follow).
+    pub fn subscribe_canonical_state(&self) -> CanonStateStream {
+        CanonStateStream { inner: BroadcastStream::new(self.canon_state.subscribe()) }
+    }
+}
+
+/// Polls the database for changes of the canonical chain and broadcasts them.
+///
+/// The canonical head of the client is moved along, so that `latest` queries see the new blocks.
+pub(crate) struct CanonStateFollower<C = RethClient> {
+    factory: ProviderFactory<Arc<DatabaseEnv>>,
+    client: C,
+    sender: broadcast::Sender<CanonStateEvent>,
+    /// The most recent canonical blocks, oldest first.
+    blocks: VecDeque<CanonicalBlock>,
+    /// The number of blocks to keep, which bounds the depth of detectable reorgs.
+    max_depth: u64,
+}
+
+impl<C: CanonChainTracker> CanonStateFollower<C> {
+    pub(crate) fn new(
+        factory: ProviderFactory<Arc<DatabaseEnv>>,
+        client: C,
+        sender: broadcast::Sender<CanonStateEvent>,
+        max_depth: u64,
+    ) -> Result<Self, ProviderError> {
+        let provider = factory.provider()?;
+        let best = provider.best_block_number()?;
+        let blocks = canonical_blocks(&provider, best.saturating_sub(max_depth), best)?.into();
+        drop(provider);
+
+        Ok(Self { factory, client, sender, blocks, max_depth })
+    }
+
+    /// Polls the database at the given interval, until `shutdown` resolves.
+    pub(crate) async fn run(mut self, interval: Duration, mut shutdown: oneshot::Receiver<()>) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {}
+            }
+            // errors are transient while the node is writing, the next poll retries
+            if let Ok(Some(event)) = self.poll() {
+                let _ = self.sender.send(event);
+            }
+        }
+    }
+
+    fn poll(&mut self) -> Result<Option<CanonStateEvent>, ProviderError> {
+        // pick up the static files the node wrote since the last poll
+        self.factory.static_file_provider().initialize_index()?;
+
+        // a fresh read transaction sees everything the node committed so far
+        let provider = self.factory.provider()?;
+        let best = provider.best_block_number()?;
+
+        // walk back to the newest block that is still canonical
+        let mut reverted = Vec::new();
+        while let Some(block) = self.blocks.back().copied() {
+            let hash: Option<EthersH256> = provider.block_hash(block.number)?.into_ethers();
+            if block.number <= best && hash == Some(block.hash) {
+                break
+            }
+            reverted.push(block);
+            self.blocks.pop_back();
+        }
+
+        let start = match self.blocks.back() {
+            Some(block) => block.number + 1,
+            None => best.saturating_sub(self.max_depth),
+        };
+        if start > best && reverted.is_empty() {
+            return Ok(None)
+        }
+
+        let committed = canonical_blocks(&provider, start, best)?;
+        self.blocks.extend(committed.iter().copied());
+        while self.blocks.len() as u64 > self.max_depth {
+            self.blocks.pop_front();
+        }
+
+        if let Some(header) = provider.sealed_header(best)? {
+            self.client.set_canonical_head(header);
+        }
+
+        reverted.reverse();
+        Ok(Some(if reverted.is_empty() {
+            CanonStateEvent::Commit { new: committed }
+        } else {
+            CanonStateEvent::Reorg { old: reverted, new: committed }
+        }))
+    }
+}
+
+/// Reads the canonical blocks in the inclusive range `start..=end`.
+fn canonical_blocks<P: BlockHashReader>(
+    provider: &P,
+    start: BlockNumber,
+    end: BlockNumber,
+) -> Result<Vec<CanonicalBlock>, ProviderError> {
+    if start > end {
+        return Ok(vec![])
+    }
+
+    Ok(provider
+        .canonical_hashes_range(start, end + 1)?
+        .into_iter()
+        .zip(start..)
+        .map(|(hash, number)| CanonicalBlock { number, hash: hash.into_ethers() })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Mutex, time::Instant};
+
+    use reth_db::{database::Database, tables, transaction::DbTxMut, DatabaseEnvKind};
+    use reth_primitives::{
+        stage::{StageCheckpoint, StageId},
+        Bytes, Header, SealedHeader, DEV,
+    };
+    use reth_rpc_types::engine::ForkchoiceState;
+
+    use super::*;
+    use crate::init::init_db;
+
+    /// Records the canonical head set by the follower.
+    #[derive(Debug, Clone, Default)]
+    struct HeadTracker(Arc<Mutex<Option<SealedHeader>>>);
+
+    impl HeadTracker {
+        fn head(&self) -> Option<BlockNumber> {
+            self.0.lock().unwrap().as_ref().map(|header| header.number)
+        }
+    }
+
+    impl CanonChainTracker for HeadTracker {
+        fn on_forkchoice_update_received(&self, _update: &ForkchoiceState) {}
+
+        fn last_received_update_timestamp(&self) -> Option<Instant> {
+            None
+        }
+
+        fn on_transition_configuration_exchanged(&self) {}
+
+        fn last_exchanged_transition_configuration_timestamp(&self) -> Option<Instant> {
+            None
+        }
+
+        fn set_canonical_head(&self, header: SealedHeader) {
+            *self.0.lock().unwrap() = Some(header);
+        }
+
+        fn set_safe(&self, _header: SealedHeader) {}
+
+        fn set_finalized(&self, _header: SealedHeader) {}
+    }
+
+    /// Returns the header of block `number` on the fork `fork`.
+    fn header(number: BlockNumber, fork: u8) -> Header {
+        Header { number, extra_data: Bytes::from(vec![fork]), ..Default::default() }
+    }
+
+    /// Makes `headers` canonical, like a node would, and moves the tip to the last one.
+    fn commit(db: &DatabaseEnv, headers: &[Header]) -> Vec<CanonicalBlock> {
+        let tx = db.tx_mut().unwrap();
+        let blocks = headers
+            .iter()
+            .map(|header| {
+                let hash = header.hash_slow();
+                tx.put::<tables::CanonicalHeaders>(header.number, hash).unwrap();
+                tx.put::<tables::HeaderNumbers>(hash, header.number).unwrap();
+                tx.put::<tables::Headers>(header.number, header.clone()).unwrap();
+                CanonicalBlock { number: header.number, hash: hash.into_ethers() }
+            })
+            .collect::<Vec<_>>();
+        if let Some(tip) = blocks.last() {
+            set_tip(&tx, tip.number);
+        }
+        tx.commit().unwrap();
+        blocks
+    }
+
+    fn set_tip(tx: &impl DbTxMut, number: BlockNumber) {
+        let checkpoint = StageCheckpoint::new(number);
+        tx.put::<tables::StageCheckpoints>(StageId::Finish.to_string(), checkpoint).unwrap();
+    }
+
+    #[test]
+    fn commits_and_reorgs() {
+        let dir = tempfile::tempdir().unwrap();
+        let static_files_path = dir.path().join("static_files");
+        std::fs::create_dir(&static_files_path).unwrap();
+        let db = Arc::new(init_db(dir.path().join("db"), DatabaseEnvKind::RW).unwrap());
+        let factory = ProviderFactory::new(db.clone(), DEV.clone(), static_files_path).unwrap();
+
+        let chain = commit(&db, &(0..=3).map(|number| header(number, 0)).collect::<Vec<_>>());
+        let tracker = HeadTracker::default();
+        let (sender, _receiver) = broadcast::channel(16);
+        let mut follower = CanonStateFollower::new(factory, tracker.clone(), sender, 64).unwrap();
+
+        assert_eq!(follower.poll().unwrap(), None);
+        assert_eq!(tracker.head(), None);
+
+        let new = commit(&db, &[header(4, 0), header(5, 0)]);
+        assert_eq!(follower.poll().unwrap(), Some(CanonStateEvent::Commit { new: new.clone() }));
+        assert_eq!(tracker.head(), Some(5));
+        assert_eq!(follower.poll().unwrap(), None);
+
+        // blocks 4 and 5 are replaced by a single block
+        let fork = commit(&db, &[header(4, 1)]);
+        assert_eq!(
+            follower.poll().unwrap(),
+            Some(CanonStateEvent::Reorg { old: new, new: fork.clone() })
+        );
+        assert_eq!(tracker.head(), Some(4));
+
+        // the tip moves back without a new block
+        let tx = db.tx_mut().unwrap();
+        set_tip(&tx, 3);
+        tx.commit().unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            Some(CanonStateEvent::Reorg { old: fork, new: vec![] })
+        );
+        assert_eq!(tracker.head(), Some(3));
+        assert_eq!(follower.blocks.back(), chain.last());
+    }
+
+    #[tokio::test]
+    async fn stops_when_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let static_files_path = dir.path().join("static_files");
+        std::fs::create_dir(&static_files_path).unwrap();
+        let db = Arc::new(init_db(dir.path().join("db"), DatabaseEnvKind::RW).unwrap());
+        let factory = ProviderFactory::new(db, DEV.clone(), static_files_path).unwrap();
+
+        let (canon_state, shutdown) = CanonStateSender::new(16);
+        let follower =
+            CanonStateFollower::new(factory, HeadTracker::default(), canon_state.events(), 64)
+                .unwrap();
+        let task = tokio::spawn(follower.run(Duration::from_millis(10), shutdown));
+
+        let clone = canon_state.clone();
+        drop(canon_state);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!task.is_finished());
+
+        drop(clone);
+        tokio::time::timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+//! Helpers to resolve the [`ChainSpec`] the middleware runs with.
+//!
+//! Besides the chains built into reth, a spec can be loaded from a geth-style genesis file or
+//! detected from the genesis header stored in the database.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use reth_db::{database::Database, tables, transaction::DbTx, DatabaseEnv, DatabaseError};
+use reth_primitives::{ChainSpec, Genesis, B256, DEV, GOERLI, HOLESKY, MAINNET, SEPOLIA};
+use reth_provider::{providers::StaticFileProvider, BlockHashReader, ProviderError};
+use thiserror::Error;
+
+/// Errors that can occur while resolving a [`ChainSpec`].
+#[derive(Error, Debug)]
+pub enum ChainSpecError {
+    /// The chain id does not belong to a chain known to reth.
+    #[error("Unsupported chain id {0}")]
+    UnsupportedChainId(u64),
+
+    /// The genesis hash in the database does not belong to a chain known to reth.
+    #[error("Unknown genesis hash {0}")]
+    UnknownGenesis(B256),
+
+    /// The database contains no genesis header.
+    #[error("Missing genesis header")]
+    MissingGenesis,
+
+    /// The genesis file could not be read.
+    #[error("Could not read genesis file {path:?}: {source}")]
+    GenesisFile { path: PathBuf, source: std::io::Error },
+
+    /// The genesis file is not a valid genesis.
+    #[error(transparent)]
+    GenesisJson(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    DatabaseError(#[from] DatabaseError),
+
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+}
+
+/// Returns the chains built into reth.
+pub fn known_chains() -> [Arc<ChainSpec>; 5] {
+    [MAINNET.clone(), GOERLI.clone(), SEPOLIA.clone(), HOLESKY.clone(), DEV.clone()]
+}
+
+/// Returns the built in [`ChainSpec`] with the given chain id.
+pub fn chain_spec_from_id(chain_id: u64) -> Result<Arc<ChainSpec>, ChainSpecError> {
+    known_chains()
+        .into_iter()
+        .find(|spec| spec.chain.id() == chain_id)
+        .ok_or(ChainSpecError::UnsupportedChainId(chain_id))
+}
+
+/// Builds a [`ChainSpec`] from a geth-style genesis file.
+pub fn chain_spec_from_genesis_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<Arc<ChainSpec>, ChainSpecError> {
+    let path = path.as_ref();
+    let raw = std::fs::read_to_string(path)
+        .map_err(|source| ChainSpecError::GenesisFile { path: path.to_path_buf(), source })?;
+    chain_spec_from_genesis_json(&raw)
+}
+
+/// Builds a [`ChainSpec`] from a geth-style genesis json string.
+pub fn chain_spec_from_genesis_json(raw: &str) -> Result<Arc<ChainSpec>, ChainSpecError> {
+    let genesis: Genesis = serde_json::from_str(raw)?;
+    Ok(Arc::new(ChainSpec::from(genesis)))
+}
+
+/// Reads the hash of the genesis header, from the database or, if the header was already moved
+/// there, from the static files.
+pub fn genesis_hash(
+    db: &DatabaseEnv,
+    static_files_path: &Path,
+) -> Result<Option<B256>, ChainSpecError> {
+    let tx = db.tx()?;
+    let hash = tx.get::<tables::CanonicalHeaders>(0)?;
+    tx.commit()?;
+
+    match hash {
+        Some(hash) => Ok(Some(hash)),
+        None => Ok(StaticFileProvider::new(static_files_path)?.block_hash(0)?),
+    }
+}
+
+/// Detects the chain of the database by matching its genesis hash against the built in chains.
+pub fn chain_spec_from_db(
+    db: &DatabaseEnv,
+    static_files_path: &Path,
+) -> Result<Arc<ChainSpec>, ChainSpecError> {
+    let hash = genesis_hash(db, static_files_path)?.ok_or(ChainSpecError::MissingGenesis)?;
+
+    known_chains()
+        .into_iter()
+        .find(|spec| spec.genesis_hash() == hash)
+        .ok_or(ChainSpecError::UnknownGenesis(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_chain_ids() {
+        assert_eq!(chain_spec_from_id(1).unwrap().genesis_hash(), MAINNET.genesis_hash());
+        assert_eq!(chain_spec_from_id(17000).unwrap().genesis_hash(), HOLESKY.genesis_hash());
+        assert!(matches!(chain_spec_from_id(42), Err(ChainSpecError::UnsupportedChainId(42))));
+    }
+
+    #[test]
+    fn genesis_json() {
+        let raw = r#"{
+            "config": {
+                "chainId": 31337,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip155Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 0,
+                "terminalTotalDifficulty": 0,
+                "shanghaiTime": 0
+            },
+            "nonce": "0x0",
+            "timestamp": "0x0",
+            "extraData": "0x",
+            "gasLimit": "0x1c9c380",
+            "difficulty": "0x0",
+            "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "coinbase": "0x0000000000000000000000000000000000000000",
+            "alloc": {
+                "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266": { "balance": "0xd3c21bcecceda1000000" }
+            }
+        }"#;
+
+        let spec = chain_spec_from_genesis_json(raw).unwrap();
+        assert_eq!(spec.chain.id(), 31337);
+        assert_eq!(spec.genesis.alloc.len(), 1);
+        assert!(chain_spec_from_genesis_json("{").is_err());
+    }
+}
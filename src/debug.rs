@@ -0,0 +1,62 @@
+//! Block level `debug_` traces that keep the outcome of every transaction.
+
+use ethers::{
+    providers::Middleware,
+    types::{
+        BlockId as EthersBlockId, GethDebugTracingOptions as EthersDebugTracingOptions,
+        GethTrace as EthersGethTrace, TxHash as EthersTxHash,
+    },
+};
+use reth_primitives::BlockId;
+use reth_rpc_types::trace::common::TraceResult;
+
+use crate::{
+    type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers, TryToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// The trace of a single transaction of a block.
+///
+/// Returned by [`RethMiddleware::debug_trace_block_detailed`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionGethTrace {
+    /// The hash of the traced transaction.
+    pub tx_hash: Option<EthersTxHash>,
+    /// The trace, or the error of the tracer.
+    pub result: Result<EthersGethTrace, String>,
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Traces all transactions of a block like [`Middleware::debug_trace_block_by_hash`] and
+    /// [`Middleware::debug_trace_block_by_number`], but returns each transaction hash alongside
+    /// its trace or the error of the tracer.
+    pub async fn debug_trace_block_detailed<T: Into<EthersBlockId>>(
+        &self,
+        block: T,
+        trace_options: EthersDebugTracingOptions,
+    ) -> Result<Vec<TransactionGethTrace>, RethMiddlewareError<M>> {
+        let block_id: BlockId = block.into().into_reth();
+        let traces =
+            self.reth_debug.debug_trace_block(block_id, trace_options.try_into_reth()?).await?;
+
+        let traces = traces
+            .into_iter()
+            .map(|trace| -> Result<_, ConversionError> {
+                Ok(match trace {
+                    TraceResult::Success { result, tx_hash } => TransactionGethTrace {
+                        tx_hash: tx_hash.into_ethers(),
+                        result: Ok(result.try_into_ethers()?),
+                    },
+                    TraceResult::Error { error, tx_hash } => {
+                        TransactionGethTrace { tx_hash: tx_hash.into_ethers(), result: Err(error) }
+                    }
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(traces)
+    }
+}
@@ -0,0 +1,906 @@
+//! A local sandbox on top of the state of a block.
+//!
+//! A [`RethFork`] executes transactions against a [`CacheDB`] over the state of the block it was
+//! forked at. Every transaction sent to the fork is mined into a block of its own right away and
+//! its changes only live in the cache, nothing is ever written to the database. Queries the fork
+//! can't answer from its own state, like blocks before the fork, fall through to the
+//! [`RethMiddleware`] it was created from.
+//!
+//! Like anvil, the fork supports snapshots and cheat methods that set the balance, nonce, code
+//! and storage of accounts or move the time of the next block.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::{
+    providers::{JsonRpcError, Middleware, MiddlewareError, PendingTransaction},
+    types::{
+        transaction::eip2718::TypedTransaction, Address as EthersAddress, Block as EthersBlock,
+        BlockId as EthersBlockId, BlockNumber as EthersBlockNumber, Bytes as EthersBytes,
+        Filter as EthersFilter, FilterBlockOption as EthersFilterBlockOption,
+        Log as EthersLog, NameOrAddress, Transaction as EthersTransaction,
+        TransactionReceipt as EthersTransactionReceipt, TxHash as EthersTxHash,
+        H256 as EthersH256, U256 as EthersU256, U64 as EthersU64,
+    },
+};
+use reth_primitives::{
+    keccak256, Address, BlockId, BlockNumber, BlockNumberOrTag, Bloom, Bytes, B256, U256,
+};
+use reth_provider::{BlockReaderIdExt, StateProviderBox, StateProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::{AccountState, CacheDB, DbAccount},
+    primitives::{
+        AccountInfo, BlockEnv, Bytecode, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult,
+        HashMap, HashSet, Output, ResultAndState, TransactTo, TxEnv,
+    },
+    Database, DatabaseCommit, Evm,
+};
+use reth_rpc::eth::error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError};
+use reth_rpc_types::{Filter, FilteredParams, Log};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use crate::{
+    type_conversions::{ToEthers, ToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// The database transactions sent to a [`RethFork`] are executed on.
+pub type ForkDb = CacheDB<StateProviderDatabase<StateProviderBox>>;
+
+/// Seconds between the timestamps of two blocks mined by a [`RethFork`].
+pub const DEFAULT_BLOCK_TIME: u64 = 12;
+
+#[derive(Error, Debug)]
+pub enum RethForkError<M: Middleware> {
+    /// An error occurred in the middleware the fork was created from, or while executing a
+    /// transaction.
+    #[error(transparent)]
+    MiddlewareError(#[from] RethMiddlewareError<M>),
+
+    /// A transaction sent to the fork has no sender.
+    #[error("Transaction sender unset")]
+    MissingSender,
+
+    /// A block timestamp isn't after the timestamp of the latest block.
+    #[error("Timestamp {0} is not after the latest block")]
+    TimestampNotIncreasing(u64),
+}
+
+impl<M: Middleware> From<EthApiError> for RethForkError<M> {
+    fn from(e: EthApiError) -> Self {
+        RethForkError::MiddlewareError(e.into())
+    }
+}
+
+impl<M: Middleware> MiddlewareError for RethForkError<M> {
+    type Inner = RethMiddlewareError<M>;
+
+    fn from_err(e: Self::Inner) -> Self {
+        RethForkError::MiddlewareError(e)
+    }
+
+    fn as_inner(&self) -> Option<&Self::Inner> {
+        match self {
+            RethForkError::MiddlewareError(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            RethForkError::MiddlewareError(e) => e.as_error_response(),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            RethForkError::MiddlewareError(e) => e.as_serde_error(),
+            _ => None,
+        }
+    }
+}
+
+/// A sandbox executing transactions on top of the state of a block, without writing to the
+/// database.
+///
+/// The fork keeps a read transaction of the database open for as long as it lives.
+#[derive(Debug)]
+pub struct RethFork<M> {
+    middleware: RethMiddleware<M>,
+    fork_block: BlockNumber,
+    cfg: CfgEnvWithHandlerCfg,
+    state: Arc<Mutex<ForkState>>,
+}
+
+/// The mutable state of a fork.
+struct ForkState {
+    db: ForkDb,
+    chain: ForkChain,
+    /// The snapshots by id, ids are never reused.
+    snapshots: BTreeMap<u64, Snapshot>,
+    next_snapshot: u64,
+}
+
+impl std::fmt::Debug for ForkState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ForkState")
+            .field("chain", &self.chain)
+            .field("snapshots", &self.snapshots.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The blocks mined by a fork.
+#[derive(Debug, Clone)]
+struct ForkChain {
+    /// The environment of the next block.
+    block_env: BlockEnv,
+    /// The hash of the latest block.
+    head: B256,
+    /// The timestamp of the latest block.
+    head_timestamp: U256,
+    /// The seconds added to the timestamps of the blocks by [`RethFork::evm_increase_time`].
+    time_offset: u64,
+    /// Senders allowed to send transactions although they are contracts.
+    impersonated: HashSet<Address>,
+    blocks: Vec<EthersBlock<EthersTxHash>>,
+    transactions: HashMap<EthersTxHash, EthersTransaction>,
+    receipts: HashMap<EthersTxHash, EthersTransactionReceipt>,
+}
+
+/// The state of a fork at the time of [`RethFork::evm_snapshot`].
+struct Snapshot {
+    accounts: HashMap<Address, DbAccount>,
+    contracts: HashMap<B256, Bytecode>,
+    block_hashes: HashMap<U256, B256>,
+    chain: ForkChain,
+}
+
+impl<M> RethFork<M>
+where
+    M: Middleware,
+{
+    /// Forks the state of `block`, the latest block if `None`.
+    pub async fn new(
+        middleware: RethMiddleware<M>,
+        block: Option<EthersBlockId>,
+    ) -> Result<Self, RethMiddlewareError<M>> {
+        let block_id =
+            block.map_or(BlockId::Number(BlockNumberOrTag::Latest), |block| block.into_reth());
+        let provider = middleware.reth_api.provider();
+        let header =
+            provider.sealed_header_by_id(block_id)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let (cfg, mut block_env, _) = middleware.reth_api.evm_env_at(header.hash().into()).await?;
+        let state = provider.state_by_block_hash(header.hash())?;
+        let db = CacheDB::new(StateProviderDatabase::new(state));
+
+        block_env.number += U256::from(1);
+        block_env.timestamp += U256::from(DEFAULT_BLOCK_TIME);
+        let chain = ForkChain {
+            block_env,
+            head: header.hash(),
+            head_timestamp: U256::from(header.timestamp),
+            time_offset: 0,
+            impersonated: HashSet::default(),
+            blocks: Vec::new(),
+            transactions: HashMap::default(),
+            receipts: HashMap::default(),
+        };
+
+        Ok(Self {
+            middleware,
+            fork_block: header.number,
+            cfg,
+            state: Arc::new(Mutex::new(ForkState {
+                db,
+                chain,
+                snapshots: BTreeMap::new(),
+                next_snapshot: 0,
+            })),
+        })
+    }
+
+    /// Returns the number of the block the fork was created at.
+    pub fn fork_block(&self) -> BlockNumber {
+        self.fork_block
+    }
+
+    /// Executes `tx` in a block of its own and returns its receipt.
+    ///
+    /// The transaction isn't signed, any sender is accepted. An unset nonce defaults to the nonce
+    /// of the sender, an unset gas limit to the block gas limit and an unset gas price to the
+    /// base fee. Reverted transactions are mined with a failed status.
+    pub async fn mine_transaction<T: Into<TypedTransaction>>(
+        &self,
+        tx: T,
+    ) -> Result<EthersTransactionReceipt, RethForkError<M>> {
+        let mut tx = tx.into();
+        let from = *tx.from().ok_or(RethForkError::MissingSender)?;
+        self.resolve_to(&mut tx).await?;
+        let cfg = self.cfg.clone();
+
+        self.with_state(move |state| state.mine(cfg, from, tx)).await
+    }
+
+    /// Takes a snapshot of the state of the fork and returns its id.
+    pub async fn evm_snapshot(&self) -> EthersU256 {
+        let mut state = self.state.lock().await;
+        let snapshot = Snapshot {
+            accounts: state.db.accounts.clone(),
+            contracts: state.db.contracts.clone(),
+            block_hashes: state.db.block_hashes.clone(),
+            chain: state.chain.clone(),
+        };
+        let id = state.next_snapshot;
+        state.next_snapshot += 1;
+        state.snapshots.insert(id, snapshot);
+
+        EthersU256::from(id)
+    }
+
+    /// Reverts the fork to the snapshot `id`, which is removed together with all snapshots taken
+    /// after it. Returns `false` if there is no such snapshot.
+    pub async fn evm_revert(&self, id: EthersU256) -> bool {
+        let mut state = self.state.lock().await;
+        let Ok(id) = u64::try_from(id) else { return false };
+        let Some(snapshot) = state.snapshots.remove(&id) else { return false };
+        state.snapshots.split_off(&id);
+
+        state.db.accounts = snapshot.accounts;
+        state.db.contracts = snapshot.contracts;
+        state.db.block_hashes = snapshot.block_hashes;
+        state.chain = snapshot.chain;
+        true
+    }
+
+    /// Sets the balance of `address`, like `anvil_setBalance`.
+    pub async fn set_balance(
+        &self,
+        address: EthersAddress,
+        balance: EthersU256,
+    ) -> Result<(), RethForkError<M>> {
+        let (address, balance) = (address.into_reth(), balance.into_reth());
+        self.with_state(move |state| {
+            state.modify_account(address, |info| info.balance = balance)
+        })
+        .await
+    }
+
+    /// Sets the nonce of `address`, like `anvil_setNonce`.
+    pub async fn set_nonce(
+        &self,
+        address: EthersAddress,
+        nonce: u64,
+    ) -> Result<(), RethForkError<M>> {
+        let address = address.into_reth();
+        self.with_state(move |state| state.modify_account(address, |info| info.nonce = nonce)).await
+    }
+
+    /// Sets the code of `address`, like `anvil_setCode`.
+    pub async fn set_code(
+        &self,
+        address: EthersAddress,
+        code: EthersBytes,
+    ) -> Result<(), RethForkError<M>> {
+        let address = address.into_reth();
+        let code = Bytecode::new_raw(code.into_reth());
+        self.with_state(move |state| {
+            state.modify_account(address, |info| {
+                info.code_hash = code.hash_slow();
+                info.code = Some(code);
+            })
+        })
+        .await
+    }
+
+    /// Sets the storage slot `slot` of `address`, like `anvil_setStorageAt`.
+    pub async fn set_storage_at(
+        &self,
+        address: EthersAddress,
+        slot: EthersH256,
+        value: EthersH256,
+    ) -> Result<(), RethForkError<M>> {
+        let address = address.into_reth();
+        let (slot, value) = (U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0));
+        self.with_state(move |state| {
+            state.touch_account(address)?.storage.insert(slot, value);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Allows `address` to send transactions although it is a contract, like
+    /// `anvil_impersonateAccount`.
+    ///
+    /// Transactions sent to the fork aren't signed, so externally owned accounts don't need to be
+    /// impersonated.
+    pub async fn impersonate_account(&self, address: EthersAddress) {
+        self.state.lock().await.chain.impersonated.insert(address.into_reth());
+    }
+
+    /// Stops impersonating `address`, like `anvil_stopImpersonatingAccount`.
+    pub async fn stop_impersonating_account(&self, address: EthersAddress) {
+        self.state.lock().await.chain.impersonated.remove(&address.into_reth());
+    }
+
+    /// Moves the timestamp of the next block `seconds` forward, like `evm_increaseTime`, and
+    /// returns the total offset of all calls.
+    ///
+    /// Later blocks keep the distance of [`DEFAULT_BLOCK_TIME`] to the next block, so the offset
+    /// applies to them too.
+    pub async fn evm_increase_time(&self, seconds: u64) -> EthersU256 {
+        let mut state = self.state.lock().await;
+        state.chain.block_env.timestamp += U256::from(seconds);
+        state.chain.time_offset += seconds;
+        state.chain.time_offset.into()
+    }
+
+    /// Sets the timestamp of the next block, like `evm_setNextBlockTimestamp`.
+    ///
+    /// Later blocks keep the distance of [`DEFAULT_BLOCK_TIME`] to it.
+    pub async fn evm_set_next_block_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> Result<(), RethForkError<M>> {
+        let mut state = self.state.lock().await;
+        if U256::from(timestamp) <= state.chain.head_timestamp {
+            return Err(RethForkError::TimestampNotIncreasing(timestamp))
+        }
+        state.chain.block_env.timestamp = U256::from(timestamp);
+        Ok(())
+    }
+
+    /// Mines an empty block, like `evm_mine`, and returns its number.
+    pub async fn evm_mine(&self) -> EthersU64 {
+        let mut state = self.state.lock().await;
+        let hash = state.next_block_hash();
+        state.seal_block(hash, EthersU256::zero(), Bloom::default(), Vec::new());
+        state.head_number().into()
+    }
+
+    /// Returns `true` if `block` refers to the state of the fork rather than to a block before it.
+    ///
+    /// Blocks mined by the fork all refer to its latest state, by number or by hash.
+    async fn is_fork_state(&self, block: Option<EthersBlockId>) -> bool {
+        match block {
+            None |
+            Some(EthersBlockId::Number(EthersBlockNumber::Latest | EthersBlockNumber::Pending)) => {
+                true
+            }
+            Some(EthersBlockId::Number(EthersBlockNumber::Number(number))) => {
+                number.as_u64() > self.fork_block
+            }
+            Some(EthersBlockId::Hash(hash)) => {
+                self.state.lock().await.chain.blocks.iter().any(|block| block.hash == Some(hash))
+            }
+            _ => false,
+        }
+    }
+
+    /// Resolves the ENS name `tx` is sent to, if any.
+    async fn resolve_to(&self, tx: &mut TypedTransaction) -> Result<(), RethMiddlewareError<M>> {
+        if let Some(to @ NameOrAddress::Name(_)) = tx.to().cloned() {
+            let to = self.middleware.get_address(to).await?;
+            tx.set_to(to);
+        }
+        Ok(())
+    }
+
+    /// Runs `f` with the state of the fork on a blocking task.
+    async fn with_state<T, F>(&self, f: F) -> Result<T, RethForkError<M>>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut ForkState) -> EthResult<T> + Send + 'static,
+    {
+        let mut state = self.state.clone().lock_owned().await;
+        tokio::task::spawn_blocking(move || f(&mut state))
+            .await
+            .map_err(|_| EthApiError::InternalBlockingTaskError)?
+            .map_err(|err| self.middleware.map_revert(err).into())
+    }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware + Clone,
+{
+    /// Returns a [`RethFork`] on top of the state of `block`, the latest block if `None`.
+    pub async fn fork(
+        &self,
+        block: Option<EthersBlockId>,
+    ) -> Result<RethFork<M>, RethMiddlewareError<M>> {
+        RethFork::new(self.clone(), block).await
+    }
+}
+
+impl ForkState {
+    /// Executes `tx` without committing its changes.
+    fn transact(&mut self, cfg: CfgEnvWithHandlerCfg, tx: TxEnv) -> EthResult<ResultAndState> {
+        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, self.chain.block_env.clone(), tx);
+        let mut evm = Evm::builder().with_db(&mut self.db).with_env_with_handler_cfg(env).build();
+
+        Ok(evm.transact()?)
+    }
+
+    /// Executes and commits `tx` in a new block.
+    fn mine(
+        &mut self,
+        mut cfg: CfgEnvWithHandlerCfg,
+        from: EthersAddress,
+        mut tx: TypedTransaction,
+    ) -> EthResult<EthersTransactionReceipt> {
+        if tx.nonce().is_none() {
+            let nonce = self.db.basic(from.into_reth())?.map_or(0, |account| account.nonce);
+            tx.set_nonce(nonce);
+        }
+        if self.chain.impersonated.contains(&from.into_reth()) {
+            cfg.cfg_env.disable_eip3607 = true;
+        }
+        let block_env = self.chain.block_env.clone();
+        let tx_env = tx_env(&tx, &block_env, block_env.basefee);
+        let effective_gas_price = tx_env
+            .gas_priority_fee
+            .map_or(tx_env.gas_price, |tip| tx_env.gas_price.min(block_env.basefee + tip));
+
+        let ResultAndState { result, state } = self.transact(cfg, tx_env.clone())?;
+        self.db.commit(state);
+
+        let hash = self.next_block_hash();
+
+        // unsigned transactions have no hash of their own, so the sender is hashed in too
+        let tx_hash: EthersTxHash =
+            keccak256([tx.sighash().as_bytes(), from.as_bytes()].concat()).into_ethers();
+        let block_hash: EthersH256 = hash.into_ethers();
+        let block_number = EthersU64::from(block_env.number.saturating_to::<u64>());
+
+        let mut logs_bloom = Bloom::default();
+        let logs = result
+            .logs()
+            .iter()
+            .enumerate()
+            .map(|(index, log)| {
+                logs_bloom.accrue_log(log);
+                EthersLog {
+                    address: log.address.into_ethers(),
+                    topics: log.topics().iter().map(|topic| topic.into_ethers()).collect(),
+                    data: log.data.data.clone().into_ethers(),
+                    block_hash: Some(block_hash),
+                    block_number: Some(block_number),
+                    transaction_hash: Some(tx_hash),
+                    transaction_index: Some(EthersU64::zero()),
+                    log_index: Some(index.into()),
+                    transaction_log_index: Some(index.into()),
+                    log_type: None,
+                    removed: Some(false),
+                }
+            })
+            .collect();
+        let contract_address = match &result {
+            ExecutionResult::Success { output: Output::Create(_, address), .. } => {
+                address.map(|address| address.into_ethers())
+            }
+            _ => None,
+        };
+        let gas_used = EthersU256::from(result.gas_used());
+
+        let receipt = EthersTransactionReceipt {
+            transaction_hash: tx_hash,
+            transaction_index: EthersU64::zero(),
+            block_hash: Some(block_hash),
+            block_number: Some(block_number),
+            from,
+            to: tx.to_addr().copied(),
+            cumulative_gas_used: gas_used,
+            gas_used: Some(gas_used),
+            contract_address,
+            logs,
+            status: Some(EthersU64::from(result.is_success() as u64)),
+            logs_bloom: logs_bloom.into_ethers(),
+            effective_gas_price: Some(effective_gas_price.into_ethers()),
+            ..Default::default()
+        };
+        let transaction = EthersTransaction {
+            hash: tx_hash,
+            nonce: tx.nonce().copied().unwrap_or_default(),
+            block_hash: Some(block_hash),
+            block_number: Some(block_number),
+            transaction_index: Some(EthersU64::zero()),
+            from,
+            to: tx.to_addr().copied(),
+            value: tx.value().copied().unwrap_or_default(),
+            gas_price: Some(effective_gas_price.into_ethers()),
+            gas: EthersU256::from(tx_env.gas_limit),
+            input: tx.data().cloned().unwrap_or_default(),
+            ..Default::default()
+        };
+
+        self.seal_block(hash, gas_used, logs_bloom, vec![tx_hash]);
+        self.chain.transactions.insert(tx_hash, transaction);
+        self.chain.receipts.insert(tx_hash, receipt.clone());
+
+        Ok(receipt)
+    }
+
+    /// Returns the hash of the next block.
+    ///
+    /// Blocks of the fork aren't sealed for real, their hash commits to the parent hash and the
+    /// number only.
+    fn next_block_hash(&self) -> B256 {
+        let number = self.chain.block_env.number.saturating_to::<u64>();
+        keccak256([self.chain.head.as_slice(), &number.to_be_bytes()].concat())
+    }
+
+    /// Appends the next block and moves the block environment on to the block after it.
+    fn seal_block(
+        &mut self,
+        hash: B256,
+        gas_used: EthersU256,
+        logs_bloom: Bloom,
+        transactions: Vec<EthersTxHash>,
+    ) {
+        let block_env = &mut self.chain.block_env;
+        let block = EthersBlock {
+            hash: Some(hash.into_ethers()),
+            parent_hash: self.chain.head.into_ethers(),
+            number: Some(block_env.number.saturating_to::<u64>().into()),
+            timestamp: block_env.timestamp.into_ethers(),
+            author: Some(block_env.coinbase.into_ethers()),
+            gas_limit: block_env.gas_limit.into_ethers(),
+            gas_used,
+            base_fee_per_gas: Some(block_env.basefee.into_ethers()),
+            logs_bloom: Some(logs_bloom.into_ethers()),
+            transactions,
+            ..Default::default()
+        };
+        self.db.block_hashes.insert(block_env.number, hash);
+
+        self.chain.head = hash;
+        self.chain.head_timestamp = block_env.timestamp;
+        block_env.number += U256::from(1);
+        block_env.timestamp += U256::from(DEFAULT_BLOCK_TIME);
+        self.chain.blocks.push(block);
+    }
+
+    /// Calls `f` with the account at `address`, which exists afterwards.
+    fn modify_account(
+        &mut self,
+        address: Address,
+        f: impl FnOnce(&mut AccountInfo),
+    ) -> EthResult<()> {
+        let mut info = self.touch_account(address)?.info.clone();
+        f(&mut info);
+        self.db.insert_account_info(address, info);
+        Ok(())
+    }
+
+    /// Loads the account at `address` into the cache, marking it as existing.
+    fn touch_account(&mut self, address: Address) -> EthResult<&mut DbAccount> {
+        let account = self.db.load_account(address)?;
+        if account.account_state == AccountState::NotExisting {
+            account.account_state = AccountState::Touched;
+        }
+        Ok(account)
+    }
+
+    /// Returns the logs of the mined blocks in `from..=to` that match `filter`.
+    fn logs(&self, filter: Filter, from: BlockNumber, to: BlockNumber) -> Vec<EthersLog> {
+        let params = FilteredParams::new(Some(filter));
+
+        self.chain
+            .blocks
+            .iter()
+            .filter(|block| {
+                block.number.map_or(false, |number| (from..=to).contains(&number.as_u64()))
+            })
+            .flat_map(|block| &block.transactions)
+            .filter_map(|tx_hash| self.chain.receipts.get(tx_hash))
+            .flat_map(|receipt| &receipt.logs)
+            .filter(|log| {
+                let log: Log = (*log).clone().into_reth();
+                params.filter_address(&log) && params.filter_topics(&log)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the number of the latest block of the fork.
+    fn head_number(&self) -> BlockNumber {
+        self.chain.block_env.number.saturating_to::<u64>() - 1
+    }
+}
+
+/// Returns the environment of `tx`, unset fields default to what a node would use.
+fn tx_env(tx: &TypedTransaction, block_env: &BlockEnv, default_gas_price: U256) -> TxEnv {
+    let transact_to = match tx.to_addr() {
+        Some(to) => TransactTo::Call(to.into_reth()),
+        None => TransactTo::create(),
+    };
+    let gas_priority_fee = match tx {
+        TypedTransaction::Eip1559(tx) => tx.max_priority_fee_per_gas.map(|fee| fee.into_reth()),
+        _ => None,
+    };
+    let access_list = tx
+        .access_list()
+        .map(|access_list| {
+            access_list
+                .0
+                .iter()
+                .map(|item| {
+                    let keys = item.storage_keys.iter().map(|key| U256::from_be_bytes(key.0));
+                    (item.address.into_reth(), keys.collect())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TxEnv {
+        caller: tx.from().copied().unwrap_or_default().into_reth(),
+        gas_limit: tx.gas().map_or(block_env.gas_limit.saturating_to(), |gas| gas.as_u64()),
+        gas_price: tx.gas_price().map_or(default_gas_price, |price| price.into_reth()),
+        gas_priority_fee,
+        transact_to,
+        value: tx.value().copied().unwrap_or_default().into_reth(),
+        data: tx.data().cloned().unwrap_or_default().into_reth(),
+        nonce: tx.nonce().map(|nonce| nonce.as_u64()),
+        access_list,
+        ..Default::default()
+    }
+}
+
+/// Returns the output of a successful execution, or the error a node would return.
+fn ensure_success(result: ExecutionResult) -> EthResult<Bytes> {
+    match result {
+        ExecutionResult::Success { output, .. } => Ok(output.into_data()),
+        ExecutionResult::Revert { output, .. } => {
+            Err(RpcInvalidTransactionError::Revert(RevertError::new(output)).into())
+        }
+        ExecutionResult::Halt { reason, .. } => {
+            Err(EthApiError::EvmCustom(format!("execution halted: {reason:?}")))
+        }
+    }
+}
+
+#[async_trait]
+impl<M> Middleware for RethFork<M>
+where
+    M: Middleware,
+{
+    type Error = RethForkError<M>;
+    type Provider = M::Provider;
+    type Inner = RethMiddleware<M>;
+
+    fn inner(&self) -> &RethMiddleware<M> {
+        &self.middleware
+    }
+
+    async fn get_block_number(&self) -> Result<EthersU64, Self::Error> {
+        Ok(self.state.lock().await.head_number().into())
+    }
+
+    /// Returns the blocks mined by the fork from the fork itself, and the blocks up to the fork
+    /// block from the inner middleware.
+    async fn get_block<T: Into<EthersBlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<EthersBlock<EthersTxHash>>, Self::Error> {
+        let block_id = block_hash_or_number.into();
+        {
+            let state = self.state.lock().await;
+            let blocks = &state.chain.blocks;
+            match block_id {
+                EthersBlockId::Hash(hash) => {
+                    if let Some(block) = blocks.iter().find(|block| block.hash == Some(hash)) {
+                        return Ok(Some(block.clone()))
+                    }
+                }
+                EthersBlockId::Number(EthersBlockNumber::Latest | EthersBlockNumber::Pending) => {
+                    if let Some(block) = blocks.last() {
+                        return Ok(Some(block.clone()))
+                    }
+                }
+                EthersBlockId::Number(EthersBlockNumber::Number(number))
+                    if number.as_u64() > self.fork_block =>
+                {
+                    let number = Some(number);
+                    return Ok(blocks.iter().find(|block| block.number == number).cloned())
+                }
+                _ => {}
+            }
+        }
+
+        // without mined blocks, the latest block is the fork block
+        let block_id = match block_id {
+            EthersBlockId::Number(EthersBlockNumber::Latest | EthersBlockNumber::Pending) => {
+                self.fork_block.into()
+            }
+            block_id => block_id,
+        };
+        Ok(self.middleware.get_block(block_id).await?)
+    }
+
+    /// Mines `tx` right away, see [`RethFork::mine_transaction`].
+    ///
+    /// The returned `PendingTransaction` polls the inner provider for its receipt and never
+    /// resolves, query the receipt from the fork instead.
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        _block: Option<EthersBlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let receipt = self.mine_transaction(tx).await?;
+        Ok(PendingTransaction::new(receipt.transaction_hash, self.provider()))
+    }
+
+    async fn call(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<EthersBlockId>,
+    ) -> Result<EthersBytes, Self::Error> {
+        if !self.is_fork_state(block).await {
+            return Ok(self.middleware.call(tx, block).await?)
+        }
+
+        let mut tx = tx.clone();
+        self.resolve_to(&mut tx).await?;
+        let mut cfg = self.cfg.clone();
+        // like `eth_call`, allow contract senders and calls without a gas price
+        cfg.cfg_env.disable_eip3607 = true;
+        cfg.cfg_env.disable_base_fee = true;
+
+        let output = self
+            .with_state(move |state| {
+                let tx_env = tx_env(&tx, &state.chain.block_env, U256::ZERO);
+                ensure_success(state.transact(cfg, tx_env)?.result)
+            })
+            .await?;
+
+        Ok(output.into_ethers())
+    }
+
+    async fn get_balance<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<EthersBlockId>,
+    ) -> Result<EthersU256, Self::Error> {
+        if !self.is_fork_state(block).await {
+            return Ok(self.middleware.get_balance(from, block).await?)
+        }
+        let from: Address = self.middleware.get_address(from).await?.into_reth();
+
+        let balance = self
+            .with_state(move |state| {
+                Ok(state.db.basic(from)?.map(|account| account.balance).unwrap_or_default())
+            })
+            .await?;
+
+        Ok(balance.into_ethers())
+    }
+
+    async fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<EthersBlockId>,
+    ) -> Result<EthersU256, Self::Error> {
+        if !self.is_fork_state(block).await {
+            return Ok(self.middleware.get_transaction_count(from, block).await?)
+        }
+        let from: Address = self.middleware.get_address(from).await?.into_reth();
+
+        let nonce = self
+            .with_state(move |state| {
+                Ok(state.db.basic(from)?.map(|account| account.nonce).unwrap_or_default())
+            })
+            .await?;
+
+        Ok(nonce.into())
+    }
+
+    async fn get_code<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        block: Option<EthersBlockId>,
+    ) -> Result<EthersBytes, Self::Error> {
+        if !self.is_fork_state(block).await {
+            return Ok(self.middleware.get_code(at, block).await?)
+        }
+        let at: Address = self.middleware.get_address(at).await?.into_reth();
+
+        let code = self
+            .with_state(move |state| {
+                let Some(account) = state.db.basic(at)? else { return Ok(Bytes::new()) };
+                let code = match account.code {
+                    Some(code) => code,
+                    None => state.db.code_by_hash(account.code_hash)?,
+                };
+                Ok(code.original_bytes())
+            })
+            .await?;
+
+        Ok(code.into_ethers())
+    }
+
+    async fn get_storage_at<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        location: EthersH256,
+        block: Option<EthersBlockId>,
+    ) -> Result<EthersH256, Self::Error> {
+        if !self.is_fork_state(block).await {
+            return Ok(self.middleware.get_storage_at(from, location, block).await?)
+        }
+        let from: Address = self.middleware.get_address(from).await?.into_reth();
+        let index = U256::from_be_bytes(location.0);
+
+        let value = self.with_state(move |state| Ok(state.db.storage(from, index)?)).await?;
+
+        Ok(B256::from(value).into_ethers())
+    }
+
+    async fn get_transaction<T: Send + Sync + Into<EthersTxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<EthersTransaction>, Self::Error> {
+        let hash = transaction_hash.into();
+        if let Some(tx) = self.state.lock().await.chain.transactions.get(&hash) {
+            return Ok(Some(tx.clone()))
+        }
+
+        Ok(self.middleware.get_transaction(hash).await?)
+    }
+
+    async fn get_transaction_receipt<T: Send + Sync + Into<EthersTxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<EthersTransactionReceipt>, Self::Error> {
+        let hash = transaction_hash.into();
+        if let Some(receipt) = self.state.lock().await.chain.receipts.get(&hash) {
+            return Ok(Some(receipt.clone()))
+        }
+
+        Ok(self.middleware.get_transaction_receipt(hash).await?)
+    }
+
+    /// Returns the logs of the blocks before the fork from the inner middleware, and the logs of
+    /// the blocks mined by the fork from the fork itself.
+    async fn get_logs(&self, filter: &EthersFilter) -> Result<Vec<EthersLog>, Self::Error> {
+        let local = {
+            let state = self.state.lock().await;
+            let head = state.head_number();
+
+            let range = match filter.block_option {
+                EthersFilterBlockOption::AtBlockHash(hash) => state
+                    .chain
+                    .blocks
+                    .iter()
+                    .find(|block| block.hash == Some(hash))
+                    .and_then(|block| block.number)
+                    .map(|number| (number.as_u64(), number.as_u64())),
+                EthersFilterBlockOption::Range { from_block, to_block } => {
+                    let resolve = |block: Option<EthersBlockNumber>| match block {
+                        Some(EthersBlockNumber::Number(number)) => number.as_u64(),
+                        Some(EthersBlockNumber::Earliest) => 0,
+                        _ => head,
+                    };
+                    Some((resolve(from_block), resolve(to_block)))
+                }
+            };
+            range.map(|(from, to)| (from, to, state.logs(filter.clone().into_reth(), from, to)))
+        };
+        // a block hash that isn't one of the mined blocks
+        let Some((from, to, local_logs)) = local else {
+            return Ok(self.middleware.get_logs(filter).await?)
+        };
+
+        let mut logs = Vec::new();
+        if from <= self.fork_block {
+            let filter = filter.clone().from_block(from).to_block(to.min(self.fork_block));
+            logs = self.middleware.get_logs(&filter).await?;
+        }
+        logs.extend(local_logs);
+
+        Ok(logs)
+    }
+}
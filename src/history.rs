@@ -0,0 +1,149 @@
+//! When accounts and storage slots changed, read from the history indices of the database.
+//!
+//! Reth indexes the blocks that changed an account or a storage slot in the sharded
+//! `AccountsHistory` and `StoragesHistory` tables. The value after a change is the value before
+//! the next change, as stored in the changesets. For the latest indexed change it is read from
+//! the state at its block, since the indices can lag behind the executed blocks.
+//!
+//! The indices only cover the blocks persisted to the database, and no blocks if the node prunes
+//! its history.
+
+use ethers::{
+    providers::Middleware,
+    types::{Address as EthersAddress, H256 as EthersH256, U256 as EthersU256},
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::{sharded_key::ShardedKey, storage_sharded_key::StorageShardedKey, BlockNumberAddress},
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_primitives::{Address, BlockNumber, B256};
+use reth_provider::{AccountReader, DatabaseProviderFactory, StateProvider, StateProviderFactory};
+
+use crate::{
+    state_diff::AccountInfo,
+    type_conversions::{ToEthers, ToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Returns the numbers of the blocks that changed the account at `address`, in order.
+    pub fn account_history(
+        &self,
+        address: EthersAddress,
+    ) -> Result<Vec<BlockNumber>, RethMiddlewareError<M>> {
+        let provider = self.reth_api.provider().database_provider_ro()?;
+        Ok(account_history(provider.tx_ref(), address.into_reth())?)
+    }
+
+    /// Returns the numbers of the blocks that changed the account at `address` together with the
+    /// account after each change, `None` if it was removed.
+    pub fn account_history_with_values(
+        &self,
+        address: EthersAddress,
+    ) -> Result<Vec<(BlockNumber, Option<AccountInfo>)>, RethMiddlewareError<M>> {
+        let provider = self.reth_api.provider().database_provider_ro()?;
+        let tx = provider.tx_ref();
+        let address: Address = address.into_reth();
+        let blocks = account_history(tx, address)?;
+
+        let mut changesets = tx.cursor_dup_read::<tables::AccountChangeSets>()?;
+        let mut values = Vec::with_capacity(blocks.len());
+        for block_number in blocks.iter().skip(1) {
+            let before = changesets
+                .seek_by_key_subkey(*block_number, address)?
+                .filter(|entry| entry.address == address)
+                .and_then(|entry| entry.info);
+            values.push(before);
+        }
+        if let Some(&last) = blocks.last() {
+            let state = self.reth_api.provider().history_by_block_number(last)?;
+            values.push(state.basic_account(address)?);
+        }
+
+        let values = values.into_iter().map(|account| account.map(|account| account.into_ethers()));
+        Ok(blocks.into_iter().zip(values).collect())
+    }
+
+    /// Returns the numbers of the blocks that changed the storage slot `slot` of `address`, in
+    /// order.
+    pub fn storage_history(
+        &self,
+        address: EthersAddress,
+        slot: EthersH256,
+    ) -> Result<Vec<BlockNumber>, RethMiddlewareError<M>> {
+        let provider = self.reth_api.provider().database_provider_ro()?;
+        Ok(storage_history(provider.tx_ref(), address.into_reth(), slot.into_reth())?)
+    }
+
+    /// Returns the numbers of the blocks that changed the storage slot `slot` of `address`
+    /// together with the value of the slot after each change.
+    pub fn storage_history_with_values(
+        &self,
+        address: EthersAddress,
+        slot: EthersH256,
+    ) -> Result<Vec<(BlockNumber, EthersU256)>, RethMiddlewareError<M>> {
+        let provider = self.reth_api.provider().database_provider_ro()?;
+        let tx = provider.tx_ref();
+        let (address, slot): (Address, B256) = (address.into_reth(), slot.into_reth());
+        let blocks = storage_history(tx, address, slot)?;
+
+        let mut changesets = tx.cursor_dup_read::<tables::StorageChangeSets>()?;
+        let mut values = Vec::with_capacity(blocks.len());
+        for block_number in blocks.iter().skip(1) {
+            let before = changesets
+                .seek_by_key_subkey(BlockNumberAddress((*block_number, address)), slot)?
+                .filter(|entry| entry.key == slot)
+                .map(|entry| entry.value);
+            values.push(before.unwrap_or_default());
+        }
+        if let Some(&last) = blocks.last() {
+            let state = self.reth_api.provider().history_by_block_number(last)?;
+            values.push(state.storage(address, slot)?.unwrap_or_default());
+        }
+
+        let values = values.into_iter().map(|value| value.into_ethers());
+        Ok(blocks.into_iter().zip(values).collect())
+    }
+}
+
+/// Reads the blocks that changed `address` from all shards of its index.
+fn account_history(tx: &impl DbTx, address: Address) -> Result<Vec<BlockNumber>, DatabaseError> {
+    let mut cursor = tx.cursor_read::<tables::AccountsHistory>()?;
+    let mut blocks = Vec::new();
+
+    for entry in cursor.walk(Some(ShardedKey::new(address, 0)))? {
+        let (key, shard) = entry?;
+        if key.key != address {
+            break
+        }
+        blocks.extend(shard.iter(0).map(|block_number| block_number as BlockNumber));
+    }
+
+    Ok(blocks)
+}
+
+/// Reads the blocks that changed `slot` of `address` from all shards of its index.
+fn storage_history(
+    tx: &impl DbTx,
+    address: Address,
+    slot: B256,
+) -> Result<Vec<BlockNumber>, DatabaseError> {
+    let mut cursor = tx.cursor_read::<tables::StoragesHistory>()?;
+    let mut blocks = Vec::new();
+
+    for entry in cursor.walk(Some(StorageShardedKey::new(address, slot, 0)))? {
+        let (key, shard) = entry?;
+        if key.address != address || key.sharded_key.key != slot {
+            break
+        }
+        blocks.extend(shard.iter(0).map(|block_number| block_number as BlockNumber));
+    }
+
+    Ok(blocks)
+}
@@ -1,24 +1,33 @@
-use eyre::Context;
 use reth_beacon_consensus::BeaconConsensus;
-use reth_blockchain_tree::{
-    externals::TreeExternals, BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree,
-};
+use reth_blockchain_tree::{externals::TreeExternals, BlockchainTree, ShareableBlockchainTree};
 use reth_node_ethereum::EthEvmConfig;
 use reth_revm::EvmProcessorFactory;
 
-use crate::{noop::NoopNetwork, RethApi, RethDebug, RethFilter, RethMiddleware, RethTrace};
+use crate::{
+    builder::RethMiddlewareBuilder,
+    canon_state::{CanonStateFollower, CanonStateSender},
+    chain::{chain_spec_from_db, ChainSpecError},
+    noop::NoopNetwork, RethApi,
+    RethBundle, RethDebug, RethFilter, RethMiddleware, RethTrace, RethTxPoolApi,
+};
 use ethers::providers::Middleware;
 // Reth
-use reth_db::{database::Database, mdbx::DatabaseArguments, tables, transaction::DbTx, DatabaseEnv, DatabaseError};
-use reth_primitives::{
-    constants::ETHEREUM_BLOCK_GAS_LIMIT, ChainSpec, DEV, GOERLI, MAINNET, SEPOLIA,
+use reth_db::{
+    database::Database,
+    mdbx::DatabaseArguments,
+    tables,
+    transaction::DbTx,
+    version::{check_db_version_file, create_db_version_file, DatabaseVersionError},
+    DatabaseEnv, DatabaseEnvKind, DatabaseError,
 };
-use reth_provider::{providers::BlockchainProvider,  ProviderFactory};
+use reth_interfaces::RethError;
+use reth_primitives::ChainSpec;
+use reth_provider::{providers::BlockchainProvider, ProviderError, ProviderFactory};
 use reth_rpc::{
     eth::{
-        cache::{EthStateCache, EthStateCacheConfig}, gas_oracle::{GasPriceOracle, GasPriceOracleConfig}, EthFilterConfig, FeeHistoryCache, FeeHistoryCacheConfig
+        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
     },
-    DebugApi, EthApi, EthFilter, TraceApi,
+    DebugApi, EthApi, EthBundle, EthFilter, TraceApi, TxPoolApi,
 };
 use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
@@ -29,7 +38,12 @@ use reth_transaction_pool::{
     EthTransactionValidator, Pool, TransactionValidationTaskExecutor,
 };
 // Std
-use std::{fmt::Debug, path::Path, sync::Arc};
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
 use tokio::runtime::Handle;
 
 pub type Provider = BlockchainProvider<
@@ -43,31 +57,90 @@ pub type RethTxPool = Pool<
     InMemoryBlobStore,
 >;
 
+/// Errors that can occur while initializing a [`RethMiddleware`].
+#[derive(Error, Debug)]
+pub enum InitError {
+    /// There is no database at the given path.
+    #[error("No database found at {0:?}")]
+    MissingDatabase(PathBuf),
+
+    /// There are no static files at the given path.
+    #[error("No static files found at {0:?}")]
+    MissingStaticFiles(PathBuf),
+
+    /// A table is missing from the database.
+    #[error("Could not open table {0}")]
+    MissingTable(&'static str),
+
+    /// The `database.version` file is missing or does not match this build of reth.
+    #[error(transparent)]
+    DatabaseVersion(#[from] DatabaseVersionError),
+
+    /// The chain spec could not be resolved.
+    #[error(transparent)]
+    ChainSpec(#[from] ChainSpecError),
+
+    /// The blocking task pool could not be built.
+    #[error("Could not build blocking task pool: {0}")]
+    ThreadPool(String),
+
+    #[error(transparent)]
+    DatabaseError(#[from] DatabaseError),
+
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+
+    #[error(transparent)]
+    RethError(#[from] RethError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
 impl<M> RethMiddleware<M>
 where
     M: Middleware,
 {
-    /// Until chain spec is in reth db, we need it as an argument
+    /// Spawns the components of the middleware as configured by the builder
     pub fn try_new(
-        db_path: &Path,
+        builder: &RethMiddlewareBuilder,
         handle: Handle,
-        chain_id: u64,
-    ) -> Result<(RethApi, RethFilter, RethTrace, RethDebug), DatabaseError> {
+    ) -> Result<
+        (
+            RethApi,
+            RethFilter,
+            RethTrace,
+            RethDebug,
+            RethTxPoolApi,
+            RethBundle,
+            CanonStateSender,
+        ),
+        InitError,
+    > {
+        let db_kind = if builder.read_only { DatabaseEnvKind::RO } else { DatabaseEnvKind::RW };
+        let db = Arc::new(init_db(builder.datadir.join("db"), db_kind)?);
+
+        let static_files_path = builder.static_files_dir();
+        if builder.read_only && !static_files_path.is_dir() {
+            return Err(InitError::MissingStaticFiles(static_files_path))
+        }
+
+        let chain_spec = match builder.chain.clone() {
+            Some(chain_spec) => chain_spec,
+            None => chain_spec_from_db(&db, &static_files_path)?,
+        };
+
+        let blocking_task_pool =
+            BlockingTaskPool::build().map_err(|e| InitError::ThreadPool(e.to_string()))?;
+
         let task_manager = TaskManager::new(handle.clone());
         let task_executor = task_manager.executor();
 
         handle.spawn(task_manager);
 
-        let db = Arc::new(init_db(db_path.join("db")).unwrap());
-        let chain_spec = match chain_id {
-            1 => MAINNET.clone(),
-            5 => GOERLI.clone(),
-            11155111 => SEPOLIA.clone(),
-            1337 => DEV.clone(),
-            _ => panic!("Unsupported chain id"),
-        };
         let evm_config = EthEvmConfig::default();
-        let provider_factory = ProviderFactory::new(db.clone(), chain_spec.clone(), db_path.join("static_files")).unwrap();
+        let provider_factory =
+            ProviderFactory::new(db.clone(), chain_spec.clone(), static_files_path)?;
 
         let tree_externals = TreeExternals::new(
             provider_factory.clone(),
@@ -75,23 +148,29 @@ where
             EvmProcessorFactory::new(chain_spec.clone(), EthEvmConfig::default()),
         );
 
-        let tree_config = BlockchainTreeConfig::default();
+        let tree_config = builder.tree_config;
 
-        // let (canon_state_notification_sender, _receiver) =
-        //     tokio::sync::broadcast::channel(tree_config.max_reorg_depth() as usize * 2);
+        let (canon_state, shutdown) =
+            CanonStateSender::new(tree_config.max_reorg_depth() as usize * 2);
 
-        let tree = BlockchainTree::new(tree_externals, tree_config, None).unwrap();
+        let tree = BlockchainTree::new(tree_externals, tree_config, None)?;
         let blockchain_tree = ShareableBlockchainTree::new(tree);
 
-        let provider = BlockchainProvider::new(
-            provider_factory,
-            blockchain_tree,
-        )
-        .unwrap();
+        let provider = BlockchainProvider::new(provider_factory.clone(), blockchain_tree)?;
+
+        if let Some(interval) = builder.follow_interval {
+            let follower = CanonStateFollower::new(
+                provider_factory,
+                provider.clone(),
+                canon_state.events(),
+                tree_config.max_reorg_depth(),
+            )?;
+            task_executor.spawn(follower.run(interval, shutdown));
+        }
 
         let state_cache = EthStateCache::spawn(
             provider.clone(),
-            EthStateCacheConfig::default(),
+            builder.state_cache_config,
             evm_config.clone(),
         );
 
@@ -114,16 +193,16 @@ where
             state_cache.clone(),
             GasPriceOracle::new(
                 provider.clone(),
-                GasPriceOracleConfig::default(),
+                builder.gas_oracle_config.clone(),
                 state_cache.clone(),
             ),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().unwrap(),
-            FeeHistoryCache::new(state_cache.clone(), FeeHistoryCacheConfig::default()),
+            builder.gas_cap,
+            blocking_task_pool,
+            FeeHistoryCache::new(state_cache.clone(), builder.fee_history_cache_config),
             evm_config.clone(),
         );
 
-        let tracing_call_guard = BlockingTaskGuard::new(10);
+        let tracing_call_guard = BlockingTaskGuard::new(builder.max_tracing_requests);
 
         let reth_trace =
             TraceApi::new(provider.clone(), reth_api.clone(), tracing_call_guard.clone());
@@ -131,10 +210,14 @@ where
         let reth_debug =
             DebugApi::new(provider.clone(), reth_api.clone(), tracing_call_guard.clone());
 
+        let reth_txpool = TxPoolApi::new(tx_pool.clone());
+
+        let reth_bundle = EthBundle::new(reth_api.clone(), tracing_call_guard);
+
         let reth_filter =
-            EthFilter::new(provider, tx_pool, state_cache, EthFilterConfig::default(), Box::new(task_executor));
+            EthFilter::new(provider, tx_pool, state_cache, builder.filter_config, Box::new(task_executor));
 
-        Ok((reth_api, reth_filter, reth_trace, reth_debug))
+        Ok((reth_api, reth_filter, reth_trace, reth_debug, reth_txpool, reth_bundle, canon_state))
     }
 }
 
@@ -153,16 +236,127 @@ where
 }
 
 /// Opens up an existing database at the specified path.
-pub fn init_db<P: AsRef<Path> + Debug>(path: P) -> eyre::Result<DatabaseEnv> {
-    let _ = std::fs::create_dir_all(path.as_ref());
+///
+/// With [`DatabaseEnvKind::RO`] nothing is ever written, which makes it safe to open the datadir
+/// of a running reth node. With [`DatabaseEnvKind::RW`] the directory and its version file are
+/// created if missing.
+pub fn init_db<P: AsRef<Path> + Debug>(
+    path: P,
+    kind: DatabaseEnvKind,
+) -> Result<DatabaseEnv, InitError> {
+    let path = path.as_ref();
+
+    if is_database_empty(path) {
+        if matches!(kind, DatabaseEnvKind::RO) {
+            return Err(InitError::MissingDatabase(path.to_path_buf()))
+        }
+        std::fs::create_dir_all(path)?;
+        create_db_version_file(path)?;
+    }
+
+    // fails on a missing or incompatible `database.version` before touching mdbx
+    check_db_version_file(path)?;
 
-    let db = DatabaseEnv::open(path.as_ref(), reth_db::DatabaseEnvKind::RW, DatabaseArguments::default())?;
+    let db = DatabaseEnv::open(path, kind, DatabaseArguments::default())?;
 
     view(&db, |tx| {
-        for table in tables::Tables::ALL.iter().map(|table| table.name()) {
-            tx.inner.open_db(Some(table)).wrap_err("Could not open db.").unwrap();
-        }
-    })?;
+        tables::Tables::ALL.iter().map(|table| table.name()).try_for_each(|table| {
+            tx.inner.open_db(Some(table)).map(|_| ()).map_err(|_| InitError::MissingTable(table))
+        })
+    })??;
 
     Ok(db)
 }
+
+/// Returns `true` if there is no database at the given path yet.
+fn is_database_empty<P: AsRef<Path>>(path: P) -> bool {
+    std::fs::read_dir(path).map(|mut entries| entries.next().is_none()).unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::providers::{Http, Provider as HttpProvider};
+    use reth_db::{transaction::DbTxMut, version::db_version_file_path};
+    use reth_primitives::B256;
+
+    use super::*;
+
+    /// Builds the components of a middleware, without an inner provider.
+    fn try_new(builder: &RethMiddlewareBuilder) -> Result<(), InitError> {
+        RethMiddleware::<HttpProvider<Http>>::try_new(builder, Handle::current()).map(|_| ())
+    }
+
+    #[test]
+    fn read_only_missing_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+
+        let result = init_db(&path, DatabaseEnvKind::RO);
+        assert!(matches!(result, Err(InitError::MissingDatabase(missing)) if missing == path));
+        // nothing is created in read-only mode
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn read_write_creates_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+
+        drop(init_db(&path, DatabaseEnvKind::RW).unwrap());
+        assert!(db_version_file_path(&path).is_file());
+        init_db(&path, DatabaseEnvKind::RO).unwrap();
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(db_version_file_path(dir.path()), "0").unwrap();
+
+        for kind in [DatabaseEnvKind::RO, DatabaseEnvKind::RW] {
+            assert!(matches!(
+                init_db(dir.path(), kind),
+                Err(InitError::DatabaseVersion(DatabaseVersionError::VersionMismatch {
+                    version: 0
+                }))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_static_files() {
+        let dir = tempfile::tempdir().unwrap();
+        drop(init_db(dir.path().join("db"), DatabaseEnvKind::RW).unwrap());
+
+        let builder = RethMiddlewareBuilder::new(dir.path());
+        assert!(matches!(
+            try_new(&builder),
+            Err(InitError::MissingStaticFiles(path)) if path == dir.path().join("static_files")
+        ));
+    }
+
+    #[tokio::test]
+    async fn unsupported_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        std::fs::create_dir(dir.path().join("static_files")).unwrap();
+        drop(init_db(&path, DatabaseEnvKind::RW).unwrap());
+        let builder = RethMiddlewareBuilder::new(dir.path());
+
+        assert!(matches!(
+            try_new(&builder),
+            Err(InitError::ChainSpec(ChainSpecError::MissingGenesis))
+        ));
+
+        let genesis = B256::repeat_byte(0x42);
+        let db = init_db(&path, DatabaseEnvKind::RW).unwrap();
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::CanonicalHeaders>(0, genesis).unwrap();
+        tx.commit().unwrap();
+        drop(db);
+
+        assert!(matches!(
+            try_new(&builder),
+            Err(InitError::ChainSpec(ChainSpecError::UnknownGenesis(hash))) if hash == genesis
+        ));
+    }
+}
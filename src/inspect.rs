@@ -0,0 +1,238 @@
+//! Execution with a custom [`Inspector`], on the same state as the rest of the middleware.
+//!
+//! The inspector is moved onto a blocking task for the execution and handed back afterwards,
+//! together with the outcome of the execution. Nothing is committed to the database.
+//!
+//! Transactions of mined blocks are executed on top of the pre-block system calls of their
+//! block, like reth's tracing endpoints do.
+
+use ethers::{
+    providers::Middleware,
+    types::{transaction::eip2718::TypedTransaction, BlockId as EthersBlockId, TxHash},
+};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, BlockHashOrNumber, BlockId, BlockNumberOrTag, ChainSpec,
+    TransactionSignedEcRecovered, B256,
+};
+use reth_provider::{
+    BlockIdReader, BlockReader, ChainSpecProvider, StateProviderBox, TransactionVariant,
+};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::CacheDB,
+    inspector_handle_register,
+    primitives::{
+        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, ResultAndState,
+    },
+    state_change::pre_block_beacon_root_contract_call,
+    DatabaseCommit, Evm, Inspector,
+};
+use reth_rpc::eth::{
+    error::{EthApiError, EthResult},
+    revm_utils::EvmOverrides,
+    EthTransactions,
+};
+
+use crate::{
+    type_conversions::{ToReth, TryToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// The database an [`Inspector`] passed to the middleware observes.
+pub type InspectorDb = CacheDB<StateProviderDatabase<StateProviderBox>>;
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Executes a call like [`Middleware::call`] with `inspector` attached, and returns the
+    /// inspector with the result and state changes of the call.
+    pub async fn inspect_call<I>(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<EthersBlockId>,
+        inspector: I,
+    ) -> Result<(I, ResultAndState), RethMiddlewareError<M>>
+    where
+        I: for<'a> Inspector<&'a mut InspectorDb> + Send + 'static,
+    {
+        let at = block.map_or(BlockId::Number(BlockNumberOrTag::Latest), |block| block.into_reth());
+        let call_request = tx.clone().try_into_reth()?;
+
+        Ok(self
+            .reth_api
+            .spawn_with_call_at(call_request, at, EvmOverrides::default(), move |mut db, env| {
+                inspect(&mut db, env, inspector)
+            })
+            .await
+            .map_err(|err| self.map_revert(err))?)
+    }
+
+    /// Replays a mined transaction with `inspector` attached, on top of the transactions before
+    /// it in its block, and returns the inspector with the result and state changes of the
+    /// transaction.
+    pub async fn inspect_transaction<I>(
+        &self,
+        tx_hash: TxHash,
+        inspector: I,
+    ) -> Result<(I, ResultAndState), RethMiddlewareError<M>>
+    where
+        I: for<'a> Inspector<&'a mut InspectorDb> + Send + 'static,
+    {
+        let (tx, block) = self
+            .reth_api
+            .transaction_and_block(tx_hash.into_reth())
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        let tx = tx.into_recovered();
+        let block_hash = block.hash();
+        let (cfg, block_env, _) = self.reth_api.evm_env_at(block_hash.into()).await?;
+        let chain_spec = self.reth_api.provider().chain_spec();
+
+        let parent_hash = block.parent_hash;
+        let parent_beacon_block_root = block.parent_beacon_block_root;
+        let txs: Vec<_> = block.into_transactions_ecrecovered().collect();
+
+        Ok(self
+            .reth_api
+            .spawn_with_state_at_block(parent_hash.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                apply_pre_block_changes(
+                    &mut db,
+                    &chain_spec,
+                    &cfg,
+                    &block_env,
+                    parent_beacon_block_root,
+                )?;
+                let preceding = txs.into_iter().take_while(|block_tx| block_tx.hash() != tx.hash());
+                replay(&mut db, &cfg, &block_env, preceding)?;
+
+                let env = EnvWithHandlerCfg::new_with_cfg_env(
+                    cfg,
+                    block_env,
+                    tx_env_with_recovered(&tx),
+                );
+                inspect(&mut db, env, inspector)
+            })
+            .await?)
+    }
+
+    /// Executes all transactions of a block in order with `inspector` attached, and returns the
+    /// inspector with the result of every transaction.
+    pub async fn inspect_block<T, I>(
+        &self,
+        block: T,
+        inspector: I,
+    ) -> Result<(I, Vec<ExecutionResult>), RethMiddlewareError<M>>
+    where
+        T: Into<EthersBlockId>,
+        I: for<'a> Inspector<&'a mut InspectorDb> + Send + 'static,
+    {
+        let block_id: BlockId = block.into().into_reth();
+        let provider = self.reth_api.provider();
+        let block_hash: B256 =
+            provider.block_hash_for_id(block_id)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let block = provider
+            .block_with_senders(BlockHashOrNumber::Hash(block_hash), TransactionVariant::WithHash)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let (cfg, block_env, _) = self.reth_api.evm_env_at(block_hash.into()).await?;
+        let chain_spec = provider.chain_spec();
+
+        let parent_hash = block.parent_hash;
+        let parent_beacon_block_root = block.parent_beacon_block_root;
+        let txs: Vec<_> = block.into_transactions_ecrecovered().collect();
+
+        Ok(self
+            .reth_api
+            .spawn_with_state_at_block(parent_hash.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                apply_pre_block_changes(
+                    &mut db,
+                    &chain_spec,
+                    &cfg,
+                    &block_env,
+                    parent_beacon_block_root,
+                )?;
+                let mut inspector = inspector;
+                let mut results = Vec::with_capacity(txs.len());
+
+                for tx in txs {
+                    let env = EnvWithHandlerCfg::new_with_cfg_env(
+                        cfg.clone(),
+                        block_env.clone(),
+                        tx_env_with_recovered(&tx),
+                    );
+                    let (returned, ResultAndState { result, state }) =
+                        inspect(&mut db, env, inspector)?;
+                    db.commit(state);
+                    inspector = returned;
+                    results.push(result);
+                }
+
+                Ok((inspector, results))
+            })
+            .await?)
+    }
+}
+
+/// Executes `env` with `inspector` attached and hands the inspector back.
+fn inspect<I>(
+    db: &mut InspectorDb,
+    env: EnvWithHandlerCfg,
+    inspector: I,
+) -> EthResult<(I, ResultAndState)>
+where
+    I: for<'a> Inspector<&'a mut InspectorDb>,
+{
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_external_context(inspector)
+        .with_env_with_handler_cfg(env)
+        .append_handler_register(inspector_handle_register)
+        .build();
+    let result = evm.transact()?;
+
+    Ok((evm.context.external, result))
+}
+
+/// Applies the system calls that precede the transactions of a block, i.e. the EIP-4788 beacon
+/// root contract call, to `db`.
+pub(crate) fn apply_pre_block_changes(
+    db: &mut InspectorDb,
+    chain_spec: &ChainSpec,
+    cfg: &CfgEnvWithHandlerCfg,
+    block_env: &BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+) -> EthResult<()> {
+    pre_block_beacon_root_contract_call(
+        db,
+        chain_spec,
+        block_env.number.try_into().unwrap_or(u64::MAX),
+        cfg,
+        block_env,
+        parent_beacon_block_root,
+    )
+    .map_err(|err| EthApiError::Internal(err.into()))
+}
+
+/// Executes and commits `txs` without an inspector.
+pub(crate) fn replay(
+    db: &mut InspectorDb,
+    cfg: &CfgEnvWithHandlerCfg,
+    block_env: &BlockEnv,
+    txs: impl IntoIterator<Item = TransactionSignedEcRecovered>,
+) -> EthResult<()> {
+    for tx in txs {
+        let env = EnvWithHandlerCfg::new_with_cfg_env(
+            cfg.clone(),
+            block_env.clone(),
+            tx_env_with_recovered(&tx),
+        );
+        let mut evm = Evm::builder().with_db(&mut *db).with_env_with_handler_cfg(env).build();
+        let ResultAndState { state, .. } = evm.transact()?;
+        drop(evm);
+        db.commit(state);
+    }
+
+    Ok(())
+}
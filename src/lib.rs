@@ -1,19 +1,27 @@
 // std
-use eyre::Result;
+use init::InitError;
 use noop::NoopNetwork;
-use reth_db::DatabaseEnv;
+use reth_db::{DatabaseEnv, DatabaseError};
 use reth_interfaces::RethError;
 use reth_node_ethereum::EthEvmConfig;
+use reth_primitives::ChainSpec;
 use std::{fmt::Debug, path::Path, sync::Arc};
 
 // ethers
-use ethers::providers::{Middleware, MiddlewareError};
+use ethers::{
+    abi::Abi,
+    providers::{JsonRpcError, Middleware, MiddlewareError},
+    types::Bytes as EthersBytes,
+};
 
 //Reth
 use reth_blockchain_tree::ShareableBlockchainTree;
 use reth_provider::{providers::BlockchainProvider, ProviderError};
 use reth_revm::EvmProcessorFactory;
-use reth_rpc::{eth::error::EthApiError, DebugApi, EthApi, EthFilter, TraceApi};
+use reth_rpc::{
+    eth::error::{EthApiError, RpcInvalidTransactionError},
+    DebugApi, EthApi, EthBundle, EthFilter, TraceApi, TxPoolApi,
+};
 use reth_transaction_pool::{
     blobstore::InMemoryBlobStore, CoinbaseTipOrdering, EthPooledTransaction,
     EthTransactionValidator, Pool, TransactionValidationTaskExecutor,
@@ -22,10 +30,33 @@ use reth_transaction_pool::{
 use jsonrpsee::types::ErrorObjectOwned;
 use thiserror::Error;
 
+pub mod blob;
+pub mod builder;
+pub mod bundle;
+pub mod canon_state;
+pub mod chain;
+pub mod debug;
+pub mod fork;
+pub mod history;
 pub mod init;
+pub mod inspect;
+pub mod logs;
 pub mod middleware;
 pub mod noop;
+pub mod overrides;
+pub mod pool;
+pub mod revert;
+pub mod state_diff;
+pub mod storage;
+pub mod trace_filter;
+pub mod transport;
 pub mod type_conversions;
+pub mod watcher;
+use builder::RethMiddlewareBuilder;
+use canon_state::CanonStateSender;
+use type_conversions::ConversionError;
+use pool::TxSubmission;
+use revert::{revert_response, RevertReason};
 use tokio::runtime::Handle;
 
 pub type RethClient = BlockchainProvider<
@@ -43,6 +74,8 @@ pub type RethApi = EthApi<RethClient, RethTxPool, NoopNetwork, EthEvmConfig>;
 pub type RethFilter = EthFilter<RethClient, RethTxPool>;
 pub type RethTrace = TraceApi<RethClient, RethApi>;
 pub type RethDebug = DebugApi<RethClient, RethApi>;
+pub type RethTxPoolApi = TxPoolApi<RethTxPool>;
+pub type RethBundle = EthBundle<RethApi>;
 
 #[derive(Clone)]
 pub struct RethMiddleware<M> {
@@ -51,6 +84,15 @@ pub struct RethMiddleware<M> {
     reth_filter: RethFilter,
     reth_trace: RethTrace,
     reth_debug: RethDebug,
+    reth_txpool: RethTxPoolApi,
+    reth_bundle: RethBundle,
+    canon_state: CanonStateSender,
+    tx_submission: TxSubmission,
+    error_abi: Option<Arc<Abi>>,
+    /// Whether the provider polls a [`transport::RethTransport`] connected to this middleware.
+    serves_transport: bool,
+    /// The maximum number of blocks a single log query of the embedded filter may span.
+    max_blocks_per_filter: u64,
 }
 
 impl<M: std::fmt::Debug> std::fmt::Debug for RethMiddleware<M> {
@@ -67,21 +109,70 @@ pub enum RethMiddlewareError<M: Middleware> {
 
     /// An error occurred in the Reth API.
     #[error(transparent)]
-    RethApiError(#[from] ErrorObjectOwned),
+    RethApiError(ErrorObjectOwned),
 
     /// An error occurred in the Eth API.
     #[error(transparent)]
-    EthApiError(#[from] EthApiError),
+    EthApiError(EthApiError),
+
+    /// A call reverted.
+    #[error(
+        "execution reverted{}",
+        reason.as_ref().map(|reason| format!(": {reason}")).unwrap_or_default()
+    )]
+    Revert {
+        /// The revert data.
+        data: EthersBytes,
+        /// The decoded revert data, if it is a known error.
+        reason: Option<RevertReason>,
+        /// The error as a JSON-RPC provider would have returned it.
+        response: JsonRpcError,
+    },
 
     #[error(transparent)]
     RethError(#[from] reth_interfaces::RethError),
 
+    /// A response could not be converted into the requested type.
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
+    /// A value could not be converted between its ethers and reth representation.
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+
     /// A trace was expected but none was found.
     #[error("Missing trace")]
     MissingTrace,
 
     #[error("Chain Id unavailable")]
     ChainIdUnavailable,
+
+    /// A transaction has no sender and the inner middleware has no default sender.
+    #[error("Sender unavailable")]
+    SenderUnavailable,
+}
+
+impl<M: Middleware> From<ErrorObjectOwned> for RethMiddlewareError<M> {
+    fn from(e: ErrorObjectOwned) -> Self {
+        match revert_response(&e) {
+            Some((data, response)) => {
+                let reason = RevertReason::decode(&data, None);
+                RethMiddlewareError::Revert { data, reason, response }
+            }
+            None => RethMiddlewareError::RethApiError(e),
+        }
+    }
+}
+
+impl<M: Middleware> From<EthApiError> for RethMiddlewareError<M> {
+    fn from(e: EthApiError) -> Self {
+        match e {
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::Revert(_)) => {
+                ErrorObjectOwned::from(e).into()
+            }
+            e => RethMiddlewareError::EthApiError(e),
+        }
+    }
 }
 
 impl<M: Middleware> From<ProviderError> for RethMiddlewareError<M> {
@@ -90,6 +181,12 @@ impl<M: Middleware> From<ProviderError> for RethMiddlewareError<M> {
     }
 }
 
+impl<M: Middleware> From<DatabaseError> for RethMiddlewareError<M> {
+    fn from(e: DatabaseError) -> Self {
+        RethMiddlewareError::RethError(RethError::Database(e))
+    }
+}
+
 impl<M: Middleware> MiddlewareError for RethMiddlewareError<M> {
     type Inner = M::Error;
 
@@ -103,6 +200,23 @@ impl<M: Middleware> MiddlewareError for RethMiddlewareError<M> {
             _ => None,
         }
     }
+
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            RethMiddlewareError::MiddlewareError(e) => e.as_error_response(),
+            RethMiddlewareError::Revert { response, .. } => Some(response),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            RethMiddlewareError::MiddlewareError(e) => e.as_serde_error(),
+            RethMiddlewareError::SerdeJson(e) |
+            RethMiddlewareError::Conversion(ConversionError::SerdeJson(e)) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl<M> RethMiddleware<M>
@@ -113,11 +227,14 @@ where
         inner: M,
         db_path: P,
         handle: Handle,
-        chain_id: u64,
-    ) -> Result<Self> {
-        let (reth_api, reth_filter, reth_trace, reth_debug) =
-            Self::try_new(db_path.as_ref(), handle, chain_id)?;
-        Ok(Self { inner, reth_api, reth_filter, reth_trace, reth_debug })
+        chain: Arc<ChainSpec>,
+    ) -> Result<Self, InitError> {
+        RethMiddlewareBuilder::new(db_path).chain_spec(chain).build(inner, handle)
+    }
+
+    /// Returns a [`RethMiddlewareBuilder`] to configure the components of the middleware.
+    pub fn builder<P: AsRef<Path>>(db_path: P) -> RethMiddlewareBuilder {
+        RethMiddlewareBuilder::new(db_path)
     }
 
     pub fn reth_api(&self) -> &RethApi {
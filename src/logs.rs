@@ -0,0 +1,196 @@
+//! Log queries over block ranges larger than a single `eth_getLogs` call may span.
+//!
+//! The range of a [`LogScanner`] is split into chunks that are queried from the embedded
+//! [`RethFilter`](crate::RethFilter) concurrently. Before a chunk is queried, the `logs_bloom` of
+//! its headers is matched against the filter, so chunks without any candidate block never load
+//! a receipt.
+
+use std::ops::RangeInclusive;
+
+use ethers::{
+    providers::Middleware,
+    types::{Filter as EthersFilter, Log as EthersLog},
+};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use reth_primitives::{BlockNumber, BlockNumberOrTag};
+use reth_provider::{BlockNumReader, BlockReaderIdExt, HeaderProvider};
+use reth_rpc_api::EthFilterApiServer;
+use reth_rpc_types::{Filter, FilterBlockOption, FilteredParams};
+
+use crate::{
+    type_conversions::{ToReth, TryToEthers},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// Default number of blocks queried at once.
+///
+/// Chunk sizes are clamped to the `max_blocks_per_filter` of the embedded filter, see
+/// [`RethMiddlewareBuilder::max_blocks_per_filter`].
+///
+/// [`RethMiddlewareBuilder::max_blocks_per_filter`]:
+/// crate::builder::RethMiddlewareBuilder::max_blocks_per_filter
+pub const DEFAULT_CHUNK_SIZE: u64 = 1_000;
+
+/// Default number of chunks queried concurrently.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Streams the logs matching a filter over an arbitrarily large block range, in order.
+///
+/// Created by [`RethMiddleware::scan_logs`].
+#[must_use = "scanners do nothing unless you stream them"]
+pub struct LogScanner<'a, M> {
+    middleware: &'a RethMiddleware<M>,
+    filter: Filter,
+    chunk_size: u64,
+    concurrency: usize,
+}
+
+impl<'a, M> LogScanner<'a, M>
+where
+    M: Middleware,
+{
+    /// Sets the number of blocks queried at once, at most the `max_blocks_per_filter` of the
+    /// embedded filter.
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.clamp(1, self.middleware.max_blocks_per_filter);
+        self
+    }
+
+    /// Sets the number of chunks queried concurrently.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns the stream of logs.
+    ///
+    /// Chunks are fetched concurrently but yielded in block order. A failing chunk yields its
+    /// error in place of its logs.
+    pub fn stream(self) -> impl Stream<Item = Result<EthersLog, RethMiddlewareError<M>>> + 'a {
+        let Self { middleware, filter, chunk_size, concurrency } = self;
+
+        stream::once(async move {
+            let chunks = middleware.log_chunks(filter, chunk_size)?;
+            let logs = stream::iter(chunks)
+                .map(move |chunk| middleware.chunk_logs(chunk))
+                .buffered(concurrency)
+                .map_ok(|logs| stream::iter(logs.into_iter().map(Ok)))
+                .try_flatten();
+            Ok::<_, RethMiddlewareError<M>>(logs)
+        })
+        .try_flatten()
+    }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Returns a [`LogScanner`] for the logs matching `filter`.
+    ///
+    /// Unlike [`Middleware::get_logs`], the block range of the filter is not limited by the
+    /// configuration of the embedded filter.
+    pub fn scan_logs(&self, filter: &EthersFilter) -> LogScanner<'_, M> {
+        LogScanner {
+            middleware: self,
+            filter: filter.clone().into_reth(),
+            chunk_size: DEFAULT_CHUNK_SIZE.min(self.max_blocks_per_filter),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Streams the logs matching `filter` by querying `page_size` blocks at a time, the
+    /// in-process counterpart of [`Middleware::get_logs_paginated`].
+    pub fn scan_logs_paginated(
+        &self,
+        filter: &EthersFilter,
+        page_size: u64,
+    ) -> impl Stream<Item = Result<EthersLog, RethMiddlewareError<M>>> + '_ {
+        self.scan_logs(filter).chunk_size(page_size).concurrency(1).stream()
+    }
+
+    /// Splits the block range of `filter` into filters of at most `chunk_size` blocks.
+    fn log_chunks(
+        &self,
+        filter: Filter,
+        chunk_size: u64,
+    ) -> Result<Vec<Filter>, RethMiddlewareError<M>> {
+        let (from_block, to_block) = match &filter.block_option {
+            FilterBlockOption::Range { from_block, to_block } => (*from_block, *to_block),
+            FilterBlockOption::AtBlockHash(_) => return Ok(vec![filter]),
+        };
+
+        // like `eth_getLogs`, unset or unknown bounds default to the best block
+        let best = self.reth_api.provider().best_block_number()?;
+        let from = self.resolve_block_number(from_block, best)?;
+        let to = self.resolve_block_number(to_block, best)?.min(best);
+
+        Ok((from..=to)
+            .step_by(chunk_size as usize)
+            .map(|start| {
+                let end = start.saturating_add(chunk_size - 1).min(to);
+                filter.clone().from_block(start).to_block(end)
+            })
+            .collect())
+    }
+
+    /// Resolves `block` to its number, unset or unknown blocks resolve to `best`.
+    pub(crate) fn resolve_block_number(
+        &self,
+        block: Option<BlockNumberOrTag>,
+        best: BlockNumber,
+    ) -> Result<BlockNumber, RethMiddlewareError<M>> {
+        match block {
+            Some(block) => {
+                Ok(self.reth_api.provider().convert_block_number(block)?.unwrap_or(best))
+            }
+            None => Ok(best),
+        }
+    }
+
+    /// Returns the logs of a single chunk, skipping it if no header bloom matches the filter.
+    async fn chunk_logs(&self, filter: Filter) -> Result<Vec<EthersLog>, RethMiddlewareError<M>> {
+        let range = match &filter.block_option {
+            FilterBlockOption::Range {
+                from_block: Some(BlockNumberOrTag::Number(from)),
+                to_block: Some(BlockNumberOrTag::Number(to)),
+            } => Some(*from..=*to),
+            _ => None,
+        };
+
+        let filter = match range {
+            Some(range) => match self.bloom_matches(&filter, range)? {
+                Some(range) => filter.from_block(*range.start()).to_block(*range.end()),
+                None => return Ok(vec![]),
+            },
+            None => filter,
+        };
+
+        Ok(self.reth_filter.logs(filter).await?.try_into_ethers()?)
+    }
+
+    /// Narrows `range` to the first and last block whose `logs_bloom` may contain a matching log.
+    fn bloom_matches(
+        &self,
+        filter: &Filter,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Option<RangeInclusive<BlockNumber>>, RethMiddlewareError<M>> {
+        let address_filter = FilteredParams::address_filter(&filter.address);
+        let topics_filter = FilteredParams::topics_filter(&filter.topics);
+
+        let start = *range.start();
+        let mut matching = self
+            .reth_api
+            .provider()
+            .headers_range(range)?
+            .into_iter()
+            .zip(start..)
+            .filter(|(header, _)| {
+                FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
+                    FilteredParams::matches_topics(header.logs_bloom, &topics_filter)
+            })
+            .map(|(_, number)| number);
+
+        Ok(matching.next().map(|first| first..=matching.last().unwrap_or(first)))
+    }
+}
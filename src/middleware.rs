@@ -1,12 +1,16 @@
 use crate::{
-    type_conversions::{ToEthers, ToReth},
+    type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers, TryToReth},
+    watcher::filter_changes_into,
     RethMiddleware, RethMiddlewareError,
 };
 use async_trait::async_trait;
+use futures::TryStreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
 
 // Ether rs Types
 use ethers::{
-    providers::{Middleware, MiddlewareError},
+    providers::{FilterKind, FilterWatcher, Middleware, MiddlewareError, PendingTransaction},
     types::{
         transaction::{
             eip2718::TypedTransaction,
@@ -18,24 +22,19 @@ use ethers::{
         EIP1186ProofResponse as EthersEIP1186ProofResponse, FeeHistory as EthersFeeHistory,
         Filter as EthersFilter, GethDebugTracingCallOptions as EthersDebugTracingCallOptions,
         GethDebugTracingOptions as EthersDebugTracingOptions, GethTrace as EthersGethTrace,
-        Log as EthersLog, NameOrAddress, Trace as EthersTrace, TraceType as EthersTraceType,
-        Transaction as EthersTransaction, TransactionReceipt as EthersTransactionReceipt,
-        TxHash as EthersTxHash, H256 as EthersH256, U256 as EthersU256, U64 as EthersU64,
+        Log as EthersLog, NameOrAddress, Trace as EthersTrace, TraceFilter as EthersTraceFilter,
+        TraceType as EthersTraceType, Transaction as EthersTransaction,
+        TransactionReceipt as EthersTransactionReceipt, TxHash as EthersTxHash,
+        TxpoolContent as EthersTxpoolContent,
+        TxpoolInspect as EthersTxpoolInspect, TxpoolStatus as EthersTxpoolStatus,
+        H256 as EthersH256, U256 as EthersU256, U64 as EthersU64,
     },
 };
 
 // Reth Types
-use reth_primitives::{BlockId, B256, U64};
-use reth_rpc::eth::revm_utils::EvmOverrides;
-use reth_rpc_api::{EthApiServer, EthFilterApiServer};
-// use reth_rpc_types::trace::geth::TraceResult;
-use reth_rpc_types::{
-    trace::{
-        common::TraceResult,
-        geth::{DefaultFrame, GethTrace}, tracerequest::TraceCallRequest,
-    },
-    Filter, JsonStorageKey,
-};
+use reth_primitives::{BlockId, B256};
+use reth_rpc_api::{EthApiServer, EthFilterApiServer, TxPoolApiServer};
+use reth_rpc_types::{Filter, JsonStorageKey};
 
 impl<M> RethMiddleware<M>
 where
@@ -73,10 +72,7 @@ where
         tx: &TypedTransaction,
         block: Option<EthersBlockId>,
     ) -> Result<EthersBytes, Self::Error> {
-        let call_request = tx.into_reth();
-        let block_id = block.into_reth();
-
-        Ok(self.reth_api.call(call_request, block_id, EvmOverrides::default()).await?.into_ethers())
+        self.call_with_overrides(tx, block, None, None).await
     }
 
     async fn estimate_gas(
@@ -84,10 +80,7 @@ where
         tx: &TypedTransaction,
         block: Option<EthersBlockId>,
     ) -> Result<EthersU256, Self::Error> {
-        let call_request = tx.into_reth();
-        let block_id = block.into_reth();
-
-        Ok(self.reth_api.estimate_gas(call_request, block_id, None).await?.into_ethers())
+        self.estimate_gas_with_overrides(tx, block, None).await
     }
 
     async fn create_access_list(
@@ -95,10 +88,14 @@ where
         tx: &TypedTransaction,
         block: Option<EthersBlockId>,
     ) -> Result<EthersAccessListWithGasUsed, Self::Error> {
-        let call_request = tx.into_reth();
+        let call_request = tx.clone().try_into_reth()?;
         let block_id = block.into_reth();
 
-        let result = self.reth_api.create_access_list(call_request, block_id).await?;
+        let result = self
+            .reth_api
+            .create_access_list(call_request, block_id)
+            .await
+            .map_err(|err| self.map_revert(err))?;
 
         Ok(result.into_ethers())
     }
@@ -172,7 +169,7 @@ where
         Ok(self
             .reth_api
             .fee_history(
-                block_count.into().into_reth(),
+                block_count.into().try_into_reth()?,
                 last_block.into_reth(),
                 Some(reward_percentiles.to_vec()),
             )
@@ -193,22 +190,18 @@ where
     }
 
     async fn get_block_number(&self) -> Result<EthersU64, RethMiddlewareError<M>> {
-        Ok(self.reth_api.block_number()?.into_ethers())
+        Ok(self.reth_api.block_number()?.try_into_ethers()?)
     }
 
-    /*async fn get_block_receipts<T: Into<EthersBlockNumber> + Send + Sync>(
+    async fn get_block_receipts<T: Into<EthersBlockNumber> + Send + Sync>(
         &self,
         block: T,
     ) -> Result<Vec<EthersTransactionReceipt>, Self::Error> {
-        let block = block.into();
-        let receipts = self
-            .reth_api
-            .block_receipts(block)
-            .await?;
+        let block_id: BlockId = block.into().into_reth();
+        let receipts = self.reth_api.block_receipts(block_id).await?;
 
-        Ok(receipts)
+        Ok(receipts.unwrap_or_default().try_into_ethers()?)
     }
-    */
 
     // Transaction
 
@@ -220,24 +213,37 @@ where
             self.reth_api.transaction_by_hash(transaction_hash.into().into_reth()).await?;
 
         match maybe_transaction {
-            Some(reth_tx) => Ok(Some(reth_tx.into_ethers())),
+            Some(reth_tx) => Ok(Some(reth_tx.try_into_ethers()?)),
             None => Ok(None),
         }
     }
 
+    // The returned `PendingTransaction`s poll the inner provider, see `TxSubmission::Local`
+
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<EthersBlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let hash = self.submit_transaction(tx.into(), block).await?;
+        Ok(PendingTransaction::new(hash, self.provider()))
+    }
+
+    async fn send_raw_transaction<'a>(
+        &'a self,
+        tx: EthersBytes,
+    ) -> Result<PendingTransaction<'a, Self::Provider>, Self::Error> {
+        let hash = self.submit_raw_transaction(tx).await?;
+        Ok(PendingTransaction::new(hash, self.provider()))
+    }
+
     async fn get_transaction_receipt<T: Send + Sync + Into<EthersTxHash>>(
         &self,
         transaction_hash: T,
     ) -> Result<Option<EthersTransactionReceipt>, RethMiddlewareError<M>> {
         let hash = ethers::types::H256::from_slice(transaction_hash.into().as_bytes());
         let receipt = self.reth_api.transaction_receipt(hash.into_reth()).await?;
-        match receipt {
-            Some(receipt) => {
-                let receipt = receipt.into_ethers();
-                Ok(Some(receipt))
-            }
-            None => Ok(None),
-        }
+        Ok(receipt.try_into_ethers()?)
     }
 
     async fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
@@ -266,7 +272,7 @@ where
             }
         };
 
-        Ok(block.into_ethers())
+        Ok(block.try_into_ethers()?)
     }
 
     async fn get_uncle<T: Into<EthersBlockId> + Send + Sync>(
@@ -289,7 +295,7 @@ where
             }
         };
 
-        Ok(block.into_ethers())
+        Ok(block.try_into_ethers()?)
     }
 
     async fn get_block_with_txs<T: Into<EthersBlockId> + Send + Sync>(
@@ -305,7 +311,7 @@ where
             }
         };
 
-        Ok(block.into_ethers())
+        Ok(block.try_into_ethers()?)
     }
 
     // Logs
@@ -313,12 +319,92 @@ where
     async fn get_logs(&self, filter: &EthersFilter) -> Result<Vec<EthersLog>, Self::Error> {
         let to_reth_filter: Filter = filter.into_reth();
         let reth_logs = self.reth_filter.logs(to_reth_filter).await?;
-        Ok(reth_logs.into_ethers())
+        Ok(reth_logs.try_into_ethers()?)
     }
 
-    //TODO: Implement get_logs_paginated
-    //TODO: Implement stream event logs (watch)
-    //TODO: Watch pending tx
+    // `get_logs_paginated` returns a `LogQuery` over the inner provider, see
+    // `RethMiddleware::scan_logs_paginated` and `RethMiddleware::scan_logs`
+    // The `FilterWatcher` and `SubscriptionStream` of the watch methods poll the transport of the
+    // inner provider, so they're only served in-process by a middleware built with
+    // `RethMiddlewareBuilder::build_with_transport`, see `crate::transport`. Otherwise they fall
+    // through to the inner provider, and `RethMiddleware::watch_logs`,
+    // `RethMiddleware::watch_pool_transactions`, `RethMiddleware::watch_new_blocks` and
+    // `RethMiddleware::subscribe_new_blocks` poll the embedded node without a transport.
+    // `subscribe_blocks` isn't overridden: the `eth_subscribe` request of the inner provider
+    // already reaches the `RethTransport`
+
+    async fn watch_blocks(
+        &self,
+    ) -> Result<FilterWatcher<'_, Self::Provider, EthersH256>, Self::Error> {
+        match self.watch_filter(FilterKind::NewBlocks).await {
+            Some(watcher) => watcher,
+            None => self.inner.watch_blocks().await.map_err(RethMiddlewareError::from_err),
+        }
+    }
+
+    async fn watch<'a>(
+        &'a self,
+        filter: &EthersFilter,
+    ) -> Result<FilterWatcher<'a, Self::Provider, EthersLog>, Self::Error> {
+        match self.watch_filter(FilterKind::Logs(filter)).await {
+            Some(watcher) => watcher,
+            None => self.inner.watch(filter).await.map_err(RethMiddlewareError::from_err),
+        }
+    }
+
+    async fn watch_pending_transactions(
+        &self,
+    ) -> Result<FilterWatcher<'_, Self::Provider, EthersH256>, Self::Error> {
+        match self.watch_filter(FilterKind::PendingTransactions).await {
+            Some(watcher) => watcher,
+            None => {
+                self.inner.watch_pending_transactions().await.map_err(RethMiddlewareError::from_err)
+            }
+        }
+    }
+
+    // Filters
+
+    async fn new_filter(&self, filter: FilterKind<'_>) -> Result<EthersU256, Self::Error> {
+        let id = match filter {
+            FilterKind::Logs(filter) => self.reth_filter.new_filter(filter.into_reth()).await?,
+            FilterKind::NewBlocks => self.reth_filter.new_block_filter().await?,
+            FilterKind::PendingTransactions => {
+                self.reth_filter.new_pending_transaction_filter(None).await?
+            }
+        };
+        Ok(id.try_into_ethers()?)
+    }
+
+    async fn uninstall_filter<T: Into<EthersU256> + Send + Sync>(
+        &self,
+        id: T,
+    ) -> Result<bool, Self::Error> {
+        Ok(self.reth_filter.uninstall_filter(id.into().into_reth()).await?)
+    }
+
+    async fn get_filter_changes<T, R>(&self, id: T) -> Result<Vec<R>, Self::Error>
+    where
+        T: Into<EthersU256> + Send + Sync,
+        R: Serialize + DeserializeOwned + Send + Sync + Debug,
+    {
+        let changes = self.reth_filter.filter_changes(id.into().into_reth()).await?;
+        Ok(filter_changes_into(changes)?)
+    }
+
+    // Txpool
+
+    async fn txpool_content(&self) -> Result<EthersTxpoolContent, Self::Error> {
+        Ok(self.reth_txpool.txpool_content().await?.try_into_ethers()?)
+    }
+
+    async fn txpool_inspect(&self) -> Result<EthersTxpoolInspect, Self::Error> {
+        Ok(self.reth_txpool.txpool_inspect().await?.into_ethers())
+    }
+
+    async fn txpool_status(&self) -> Result<EthersTxpoolStatus, Self::Error> {
+        Ok(self.reth_txpool.txpool_status().await?.into_ethers())
+    }
 
     // Tracing
     async fn trace_call<T: Into<TypedTransaction> + Send + Sync>(
@@ -327,18 +413,8 @@ where
         trace_type: Vec<EthersTraceType>,
         block: Option<EthersBlockNumber>,
     ) -> Result<EthersBlockTrace, Self::Error> {
-        let trace_call = TraceCallRequest {
-            call: req.into().into_reth(),
-            trace_types: trace_type.into_reth(),
-            block_id: block.into_reth(),
-            state_overrides: None,
-            block_overrides: None,
-        };
-        let trace = self
-            .reth_trace
-            .trace_call(trace_call)
-            .await?;
-        Ok(trace.into_ethers())
+        let req: TypedTransaction = req.into();
+        self.trace_call_with_overrides(req, trace_type, block, None, None).await
     }
 
     async fn trace_call_many<T: Into<TypedTransaction> + Send + Sync>(
@@ -346,9 +422,16 @@ where
         req: Vec<(T, Vec<EthersTraceType>)>,
         block: Option<EthersBlockNumber>,
     ) -> Result<Vec<EthersBlockTrace>, Self::Error> {
-        let tx: Vec<(TypedTransaction, Vec<EthersTraceType>)> =
-            req.into_iter().map(|r| (r.0.into(), r.1)).collect();
-        Ok(self.reth_trace.trace_call_many(tx.into_reth(), block.into_reth()).await?.into_ethers())
+        let calls = req
+            .into_iter()
+            .map(|(tx, trace_type)| Ok((tx.into().try_into_reth()?, trace_type.into_reth())))
+            .collect::<Result<Vec<_>, ConversionError>>()?;
+        let traces = self
+            .reth_trace
+            .trace_call_many(calls, block.into_reth())
+            .await
+            .map_err(|err| self.map_revert(err))?;
+        Ok(traces.into_ethers())
     }
 
     async fn trace_raw_transaction(
@@ -384,13 +467,13 @@ where
             .reth_trace
             .replay_block_transactions(BlockId::Number(block.into_reth()), trace_type.into_reth())
             .await?;
-        Ok(res.unwrap().into_ethers())
+        Ok(res.ok_or(RethMiddlewareError::MissingTrace)?.into_ethers())
     }
 
     async fn trace_block(&self, block: EthersBlockNumber) -> Result<Vec<EthersTrace>, Self::Error> {
         let block_id = block.into_reth();
         let trace_opt = self.reth_trace.trace_block(BlockId::Number(block_id)).await?;
-        Ok(trace_opt.ok_or(RethMiddlewareError::MissingTrace)?.into_ethers())
+        Ok(trace_opt.ok_or(RethMiddlewareError::MissingTrace)?.try_into_ethers()?)
     }
 
     async fn debug_trace_transaction(
@@ -400,10 +483,10 @@ where
     ) -> Result<EthersGethTrace, Self::Error> {
         let debug_trace = self
             .reth_debug
-            .debug_trace_transaction(tx_hash.into_reth(), trace_options.into_reth())
+            .debug_trace_transaction(tx_hash.into_reth(), trace_options.try_into_reth()?)
             .await?;
 
-        Ok(debug_trace.into_ethers())
+        Ok(debug_trace.try_into_ethers()?)
     }
 
     async fn debug_trace_block_by_hash(
@@ -411,24 +494,15 @@ where
         block: EthersH256,
         trace_options: EthersDebugTracingOptions,
     ) -> Result<Vec<EthersGethTrace>, Self::Error> {
-        let mut debug_trace = self
+        let debug_trace = self
             .reth_debug
             .debug_trace_block(
-                BlockId::from(U64::from_be_bytes(block.0)),
-                trace_options.into_reth(),
+                EthersBlockId::Hash(block).into_reth(),
+                trace_options.try_into_reth()?,
             )
             .await?;
 
-        let mut trace = vec![];
-
-        debug_trace.iter_mut().for_each(|x| {
-            trace.push(match x {
-                TraceResult::Success { result: val, tx_hash: _ } => val.clone(),
-                TraceResult::Error { error: _, tx_hash: _ } => GethTrace::Default(DefaultFrame::default()),
-            });
-        });
-
-        Ok(trace.into_ethers())
+        Ok(debug_trace.try_into_ethers()?)
     }
 
     async fn debug_trace_block_by_number(
@@ -436,21 +510,15 @@ where
         block: Option<ethers::types::BlockNumber>,
         trace_options: EthersDebugTracingOptions,
     ) -> Result<Vec<EthersGethTrace>, Self::Error> {
-        let mut debug_trace = self
+        let debug_trace = self
             .reth_debug
-            .debug_trace_block(block.unwrap().into_reth(), trace_options.into_reth())
+            .debug_trace_block(
+                block.unwrap_or_default().into_reth(),
+                trace_options.try_into_reth()?,
+            )
             .await?;
 
-        let mut trace = vec![];
-
-        debug_trace.iter_mut().for_each(|x| {
-            trace.push(match x {
-                TraceResult::Success { result: val, tx_hash: _ } => val.clone(),
-                TraceResult::Error { error: _, tx_hash: _ } => GethTrace::Default(DefaultFrame::default()),
-            });
-        });
-
-        Ok(trace.into_ethers())
+        Ok(debug_trace.try_into_ethers()?)
     }
 
     async fn debug_trace_call<T: Into<TypedTransaction> + Send + Sync>(
@@ -459,16 +527,8 @@ where
         block_id: Option<EthersBlockId>,
         trace_options: EthersDebugTracingCallOptions,
     ) -> Result<EthersGethTrace, Self::Error> {
-        let debug_trace = self
-            .reth_debug
-            .debug_trace_call(
-                call.into().into_reth(),
-                block_id.into_reth(),
-                trace_options.into_reth(),
-            )
-            .await?;
-
-        Ok(debug_trace.into_ethers())
+        let call: TypedTransaction = call.into();
+        self.debug_trace_call_with_overrides(call, block_id, trace_options, None, None).await
     }
 
     async fn trace_get<T: Into<EthersU64> + Send + Sync>(
@@ -477,7 +537,8 @@ where
         index: Vec<T>,
     ) -> Result<EthersTrace, Self::Error> {
         let index: Vec<usize> = index.into_iter().map(|i| i.into().as_usize()).collect();
-        Ok(self.reth_trace.trace_get(hash.into_reth(), index).await?.into_ethers().unwrap())
+        let trace = self.reth_trace.trace_get(hash.into_reth(), index).await?;
+        Ok(trace.ok_or(RethMiddlewareError::MissingTrace)?.try_into_ethers()?)
     }
 
     async fn trace_transaction(
@@ -485,6 +546,10 @@ where
         tx_hash: EthersTxHash,
     ) -> Result<Vec<EthersTrace>, Self::Error> {
         let trace = self.reth_trace.trace_transaction(tx_hash.into_reth()).await?;
-        Ok(trace.into_ethers())
+        Ok(trace.unwrap_or_default().try_into_ethers()?)
+    }
+
+    async fn trace_filter(&self, filter: EthersTraceFilter) -> Result<Vec<EthersTrace>, Self::Error> {
+        self.scan_traces(&filter).stream().try_collect().await
     }
 }
@@ -0,0 +1,209 @@
+//! `call`, `estimate_gas`, `trace_call` and `debug_trace_call` against hypothetical state and
+//! block environments.
+//!
+//! State overrides use ethers' [`spoof::State`], which replaces the balance, nonce, code or
+//! storage of any account. The calls take a [`CallRequest`], so blob transactions run through the
+//! same paths as any other transaction.
+
+use ethers::{
+    providers::{spoof, Middleware},
+    types::{
+        transaction::eip2718::TypedTransaction, Address as EthersAddress,
+        BlockId as EthersBlockId, BlockNumber as EthersBlockNumber,
+        BlockTrace as EthersBlockTrace, Bytes as EthersBytes, Eip1559TransactionRequest,
+        Eip2930TransactionRequest, GethDebugTracingCallOptions as EthersDebugTracingCallOptions,
+        GethTrace as EthersGethTrace, TraceType as EthersTraceType,
+        TransactionRequest as EthersTransactionRequest, H256 as EthersH256, U256 as EthersU256,
+        U64 as EthersU64,
+    },
+};
+use reth_rpc::eth::revm_utils::EvmOverrides;
+use reth_rpc_api::EthApiServer;
+use reth_rpc_types::trace::{geth::GethDebugTracingCallOptions, tracerequest::TraceCallRequest};
+
+use crate::{
+    blob::Eip4844TransactionRequest,
+    type_conversions::{ToEthers, ToReth, TryToEthers, TryToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// A transaction executed without committing it, either one of ethers' [`TypedTransaction`]s or
+/// a blob transaction, which [`TypedTransaction`] can't express.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallRequest {
+    Typed(TypedTransaction),
+    Blob(Eip4844TransactionRequest),
+}
+
+impl From<TypedTransaction> for CallRequest {
+    fn from(tx: TypedTransaction) -> Self {
+        Self::Typed(tx)
+    }
+}
+
+impl From<&TypedTransaction> for CallRequest {
+    fn from(tx: &TypedTransaction) -> Self {
+        Self::Typed(tx.clone())
+    }
+}
+
+impl From<EthersTransactionRequest> for CallRequest {
+    fn from(tx: EthersTransactionRequest) -> Self {
+        Self::Typed(tx.into())
+    }
+}
+
+impl From<Eip2930TransactionRequest> for CallRequest {
+    fn from(tx: Eip2930TransactionRequest) -> Self {
+        Self::Typed(tx.into())
+    }
+}
+
+impl From<Eip1559TransactionRequest> for CallRequest {
+    fn from(tx: Eip1559TransactionRequest) -> Self {
+        Self::Typed(tx.into())
+    }
+}
+
+impl From<Eip4844TransactionRequest> for CallRequest {
+    fn from(tx: Eip4844TransactionRequest) -> Self {
+        Self::Blob(tx)
+    }
+}
+
+impl From<&Eip4844TransactionRequest> for CallRequest {
+    fn from(tx: &Eip4844TransactionRequest) -> Self {
+        Self::Blob(tx.clone())
+    }
+}
+
+/// Overrides of the block environment a call is executed in, unset fields keep the value of the
+/// block the call is executed on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockOverrides {
+    pub number: Option<EthersU256>,
+    pub difficulty: Option<EthersU256>,
+    pub timestamp: Option<EthersU64>,
+    pub gas_limit: Option<EthersU64>,
+    pub coinbase: Option<EthersAddress>,
+    pub random: Option<EthersH256>,
+    pub base_fee: Option<EthersU256>,
+}
+
+impl BlockOverrides {
+    pub fn number<T: Into<EthersU256>>(mut self, number: T) -> Self {
+        self.number = Some(number.into());
+        self
+    }
+
+    pub fn timestamp<T: Into<EthersU64>>(mut self, timestamp: T) -> Self {
+        self.timestamp = Some(timestamp.into());
+        self
+    }
+
+    pub fn base_fee<T: Into<EthersU256>>(mut self, base_fee: T) -> Self {
+        self.base_fee = Some(base_fee.into());
+        self
+    }
+
+    pub fn coinbase(mut self, coinbase: EthersAddress) -> Self {
+        self.coinbase = Some(coinbase);
+        self
+    }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// [`Middleware::call`] with the given state and block overrides applied.
+    pub async fn call_with_overrides<T: Into<CallRequest>>(
+        &self,
+        tx: T,
+        block: Option<EthersBlockId>,
+        state: Option<spoof::State>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<EthersBytes, RethMiddlewareError<M>> {
+        let call_request = tx.into().try_into_reth()?;
+        let block_id = block.into_reth();
+        let overrides = EvmOverrides::new(
+            state.try_into_reth()?,
+            block_overrides.map(|overrides| Box::new(overrides.into_reth())),
+        );
+
+        let output = self
+            .reth_api
+            .call(call_request, block_id, overrides)
+            .await
+            .map_err(|err| self.map_revert(err))?;
+
+        Ok(output.into_ethers())
+    }
+
+    /// [`Middleware::estimate_gas`] with the given state overrides applied.
+    ///
+    /// `eth_estimateGas` doesn't support block overrides.
+    pub async fn estimate_gas_with_overrides<T: Into<CallRequest>>(
+        &self,
+        tx: T,
+        block: Option<EthersBlockId>,
+        state: Option<spoof::State>,
+    ) -> Result<EthersU256, RethMiddlewareError<M>> {
+        let call_request = tx.into().try_into_reth()?;
+        let block_id = block.into_reth();
+
+        let gas = self
+            .reth_api
+            .estimate_gas(call_request, block_id, state.try_into_reth()?)
+            .await
+            .map_err(|err| self.map_revert(err))?;
+
+        Ok(gas.into_ethers())
+    }
+
+    /// [`Middleware::trace_call`] with the given state and block overrides applied.
+    pub async fn trace_call_with_overrides<T: Into<CallRequest>>(
+        &self,
+        req: T,
+        trace_type: Vec<EthersTraceType>,
+        block: Option<EthersBlockNumber>,
+        state: Option<spoof::State>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<EthersBlockTrace, RethMiddlewareError<M>> {
+        let trace_call = TraceCallRequest {
+            call: req.into().try_into_reth()?,
+            trace_types: trace_type.into_reth(),
+            block_id: block.into_reth(),
+            state_overrides: state.try_into_reth()?,
+            block_overrides: block_overrides.map(|overrides| Box::new(overrides.into_reth())),
+        };
+
+        let trace =
+            self.reth_trace.trace_call(trace_call).await.map_err(|err| self.map_revert(err))?;
+        Ok(trace.into_ethers())
+    }
+
+    /// [`Middleware::debug_trace_call`] with the given state and block overrides applied.
+    pub async fn debug_trace_call_with_overrides<T: Into<CallRequest>>(
+        &self,
+        call: T,
+        block: Option<EthersBlockId>,
+        trace_options: EthersDebugTracingCallOptions,
+        state: Option<spoof::State>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<EthersGethTrace, RethMiddlewareError<M>> {
+        let opts = GethDebugTracingCallOptions {
+            state_overrides: state.try_into_reth()?,
+            block_overrides: block_overrides.map(|overrides| overrides.into_reth()),
+            ..trace_options.try_into_reth()?
+        };
+
+        let debug_trace = self
+            .reth_debug
+            .debug_trace_call(call.into().try_into_reth()?, block.into_reth(), opts)
+            .await
+            .map_err(|err| self.map_revert(err))?;
+
+        Ok(debug_trace.try_into_ethers()?)
+    }
+}
@@ -0,0 +1,216 @@
+//! The embedded transaction pool.
+
+use std::{
+    future::{Future, IntoFuture},
+    ops::Deref,
+    pin::Pin,
+    time::Duration,
+};
+
+use ethers::{
+    providers::{Middleware, MiddlewareError, DEFAULT_LOCAL_POLL_INTERVAL},
+    types::{
+        transaction::eip2718::TypedTransaction, BlockId as EthersBlockId, Bytes as EthersBytes,
+        TransactionReceipt as EthersTransactionReceipt, TxHash as EthersTxHash,
+    },
+};
+use reth_rpc_api::EthApiServer;
+use reth_transaction_pool::TransactionPool;
+
+use crate::{
+    type_conversions::{ToEthers, ToReth, TryToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// Where [`Middleware::send_raw_transaction`] and [`Middleware::send_transaction`] submit
+/// transactions to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TxSubmission {
+    /// Transactions are sent to the inner provider only.
+    #[default]
+    Inner,
+    /// Transactions are validated and inserted into the embedded pool only.
+    ///
+    /// The embedded node has no accounts, so unsigned transactions are rejected. The
+    /// `PendingTransaction` returned by the [`Middleware`] methods polls the inner provider for
+    /// its receipt and never resolves unless the transaction reaches the network some other way,
+    /// use [`RethMiddleware::send_local_transaction`] for a handle that resolves against the
+    /// database instead.
+    Local,
+    /// Transactions are inserted into the embedded pool and, if valid, sent to the inner provider.
+    ///
+    /// Unsigned transactions are filled and signed by the inner middleware first, see
+    /// [`Middleware::sign_transaction`], so it must have a signer for the sender.
+    LocalAndForward,
+}
+
+impl TxSubmission {
+    /// Returns `true` if transactions are inserted into the embedded pool.
+    pub fn is_local(&self) -> bool {
+        matches!(self, TxSubmission::Local | TxSubmission::LocalAndForward)
+    }
+
+    /// Returns `true` if transactions are sent to the inner provider.
+    pub fn is_forwarded(&self) -> bool {
+        matches!(self, TxSubmission::Inner | TxSubmission::LocalAndForward)
+    }
+}
+
+/// A transaction in the embedded pool, which resolves to its receipt once it is included in a
+/// block of the database, the in-process counterpart of
+/// [`PendingTransaction`](ethers::providers::PendingTransaction).
+///
+/// Resolves to `None` if the transaction leaves the pool without being included. Blocks written
+/// by a running node are only seen when the middleware follows it, see
+/// [`RethMiddlewareBuilder::follow`](crate::builder::RethMiddlewareBuilder::follow).
+#[must_use = "pending transactions do nothing unless you await them"]
+#[derive(Debug)]
+pub struct PendingPoolTransaction<'a, M> {
+    tx_hash: EthersTxHash,
+    middleware: &'a RethMiddleware<M>,
+    interval: Duration,
+}
+
+impl<'a, M> PendingPoolTransaction<'a, M>
+where
+    M: Middleware,
+{
+    /// Creates a new handle for the transaction with the given hash.
+    pub fn new(tx_hash: EthersTxHash, middleware: &'a RethMiddleware<M>) -> Self {
+        Self { tx_hash, middleware, interval: DEFAULT_LOCAL_POLL_INTERVAL }
+    }
+
+    /// Sets the interval at which the database and the pool are polled.
+    pub fn interval(mut self, duration: Duration) -> Self {
+        self.interval = duration;
+        self
+    }
+
+    /// Returns the hash of the transaction.
+    pub fn tx_hash(&self) -> EthersTxHash {
+        self.tx_hash
+    }
+}
+
+impl<M> Deref for PendingPoolTransaction<'_, M> {
+    type Target = EthersTxHash;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx_hash
+    }
+}
+
+impl<'a, M> IntoFuture for PendingPoolTransaction<'a, M>
+where
+    M: Middleware + 'a,
+{
+    type Output = Result<Option<EthersTransactionReceipt>, RethMiddlewareError<M>>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+
+                let receipt = self.middleware.get_transaction_receipt(self.tx_hash).await?;
+                if receipt.is_some() {
+                    return Ok(receipt)
+                }
+                if !self.middleware.reth_api.pool().contains(&self.tx_hash.into_reth()) {
+                    // the transaction may have been included since its receipt was checked
+                    return self.middleware.get_transaction_receipt(self.tx_hash).await
+                }
+            }
+        })
+    }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Returns where transactions are submitted to, see
+    /// [`crate::builder::RethMiddlewareBuilder::tx_submission`].
+    pub fn tx_submission(&self) -> TxSubmission {
+        self.tx_submission
+    }
+
+    /// Validates a signed transaction and inserts it into the embedded pool, regardless of the
+    /// configured [`TxSubmission`].
+    pub async fn send_local_transaction(
+        &self,
+        tx: EthersBytes,
+    ) -> Result<PendingPoolTransaction<'_, M>, RethMiddlewareError<M>> {
+        let hash = self
+            .reth_api
+            .send_raw_transaction(tx.into_reth())
+            .await
+            .map_err(|err| self.map_revert(err))?;
+        Ok(PendingPoolTransaction::new(hash.into_ethers(), self))
+    }
+
+    /// Submits a signed transaction as configured by [`TxSubmission`] and returns its hash.
+    pub(crate) async fn submit_raw_transaction(
+        &self,
+        tx: EthersBytes,
+    ) -> Result<EthersTxHash, RethMiddlewareError<M>> {
+        if self.tx_submission.is_local() {
+            let hash = *self.send_local_transaction(tx.clone()).await?;
+            if !self.tx_submission.is_forwarded() {
+                return Ok(hash)
+            }
+        }
+
+        let pending =
+            self.inner.send_raw_transaction(tx).await.map_err(RethMiddlewareError::from_err)?;
+        Ok(*pending)
+    }
+
+    /// Submits an unsigned transaction as configured by [`TxSubmission`] and returns its hash.
+    pub(crate) async fn submit_transaction(
+        &self,
+        tx: TypedTransaction,
+        block: Option<EthersBlockId>,
+    ) -> Result<EthersTxHash, RethMiddlewareError<M>> {
+        match self.tx_submission {
+            TxSubmission::Inner => {}
+            // fails unless the embedded node has a signer for the sender, which it never has
+            TxSubmission::Local => {
+                let hash = self
+                    .reth_api
+                    .send_transaction(tx.try_into_reth()?)
+                    .await
+                    .map_err(|err| self.map_revert(err))?;
+                return Ok(hash.into_ethers())
+            }
+            TxSubmission::LocalAndForward => {
+                // the pool only takes signed transactions, so sign with the inner signer and
+                // forward only once the pool accepted the transaction
+                let mut tx = tx;
+                self.inner
+                    .fill_transaction(&mut tx, block)
+                    .await
+                    .map_err(RethMiddlewareError::from_err)?;
+                if tx.chain_id().is_none() {
+                    tx.set_chain_id(self.get_chainid().await?.as_u64());
+                }
+                let from = tx
+                    .from()
+                    .copied()
+                    .or_else(|| self.inner.default_sender())
+                    .ok_or(RethMiddlewareError::SenderUnavailable)?;
+                let signature = self
+                    .inner
+                    .sign_transaction(&tx, from)
+                    .await
+                    .map_err(RethMiddlewareError::from_err)?;
+                return self.submit_raw_transaction(tx.rlp_signed(&signature)).await
+            }
+        }
+
+        let pending =
+            self.inner.send_transaction(tx, block).await.map_err(RethMiddlewareError::from_err)?;
+        Ok(*pending)
+    }
+}
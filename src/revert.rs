@@ -0,0 +1,190 @@
+//! Decoding of the revert data of failed calls.
+
+use std::fmt;
+
+use ethers::{
+    abi::{self, Abi, ParamType, Token},
+    providers::{JsonRpcError, Middleware},
+    types::{Bytes as EthersBytes, U256 as EthersU256},
+};
+use jsonrpsee::types::ErrorObjectOwned;
+
+use crate::{RethMiddleware, RethMiddlewareError};
+
+/// The JSON-RPC error code of a reverted call.
+pub const REVERT_ERROR_CODE: i32 = 3;
+
+/// Selector of `Error(string)`, the error of `require` and `revert` with a message.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`, the error of failed assertions, arithmetic errors and the like.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// The decoded revert data of a call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// `Error(string)`
+    Error(String),
+    /// `Panic(uint256)`, see [`RevertReason::panic_description`].
+    Panic(EthersU256),
+    /// A custom error of the ABI registered with
+    /// [`RethMiddlewareBuilder::error_abi`](crate::builder::RethMiddlewareBuilder::error_abi).
+    Custom { name: String, args: Vec<Token> },
+}
+
+impl RevertReason {
+    /// Decodes `Error(string)` and `Panic(uint256)`, and the custom errors of `abi` if given.
+    pub fn decode(data: &[u8], abi: Option<&Abi>) -> Option<Self> {
+        if data.len() < 4 {
+            return None
+        }
+        let (selector, args) = data.split_at(4);
+
+        if selector == ERROR_SELECTOR {
+            let mut tokens = abi::decode(&[ParamType::String], args).ok()?;
+            return tokens.pop()?.into_string().map(RevertReason::Error)
+        }
+        if selector == PANIC_SELECTOR {
+            let mut tokens = abi::decode(&[ParamType::Uint(256)], args).ok()?;
+            return tokens.pop()?.into_uint().map(RevertReason::Panic)
+        }
+
+        abi?.errors().find_map(|error| {
+            if error.signature()[..4] != *selector {
+                return None
+            }
+            let args = error.decode(args).ok()?;
+            Some(RevertReason::Custom { name: error.name.clone(), args })
+        })
+    }
+
+    /// Returns what a `Panic(uint256)` code means, as documented by Solidity.
+    pub fn panic_description(code: EthersU256) -> Option<&'static str> {
+        if code > EthersU256::from(u8::MAX) {
+            return None
+        }
+        Some(match code.as_u32() {
+            0x00 => "generic compiler panic",
+            0x01 => "assertion failed",
+            0x11 => "arithmetic overflow or underflow",
+            0x12 => "division or modulo by zero",
+            0x21 => "invalid enum value",
+            0x22 => "invalid storage byte array encoding",
+            0x31 => "pop on an empty array",
+            0x32 => "array index out of bounds",
+            0x41 => "out of memory",
+            0x51 => "call to an uninitialized function",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Error(reason) => f.write_str(reason),
+            RevertReason::Panic(code) => match RevertReason::panic_description(*code) {
+                Some(description) => write!(f, "panic: {description} ({code:#x})"),
+                None => write!(f, "panic: {code:#x}"),
+            },
+            RevertReason::Custom { name, args } => {
+                let args = args.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                write!(f, "{name}({args})")
+            }
+        }
+    }
+}
+
+/// Returns the revert data and the error as a JSON-RPC provider would have returned it, if `err`
+/// is a reverted call.
+pub(crate) fn revert_response(err: &ErrorObjectOwned) -> Option<(EthersBytes, JsonRpcError)> {
+    if err.code() != REVERT_ERROR_CODE {
+        return None
+    }
+    // a revert without data, e.g. a bare `revert()`, has no data field at all
+    let (revert_data, data) = match err.data() {
+        Some(data) => {
+            let data: serde_json::Value = serde_json::from_str(data.get()).ok()?;
+            (serde_json::from_value(data.clone()).ok()?, Some(data))
+        }
+        None => (EthersBytes::new(), None),
+    };
+
+    let response = JsonRpcError {
+        code: err.code() as i64,
+        message: err.message().to_string(),
+        data,
+    };
+    Some((revert_data, response))
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Decodes revert data, including the custom errors of the registered error ABI.
+    pub fn decode_revert(&self, data: &[u8]) -> Option<RevertReason> {
+        RevertReason::decode(data, self.error_abi.as_deref())
+    }
+
+    /// Converts an error of a call into a [`RethMiddlewareError`], decoding the revert data with
+    /// the registered error ABI too.
+    ///
+    /// The conversion from the errors of the embedded node only decodes `Error(string)` and
+    /// `Panic(uint256)`, so every path that executes a call maps its errors with this.
+    pub(crate) fn map_revert<E>(&self, err: E) -> RethMiddlewareError<M>
+    where
+        E: Into<RethMiddlewareError<M>>,
+    {
+        match err.into() {
+            RethMiddlewareError::Revert { data, reason: None, response } => {
+                let reason = self.decode_revert(&data);
+                RethMiddlewareError::Revert { data, reason, response }
+            }
+            err => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        abi::{self, Abi, Token},
+        types::U256 as EthersU256,
+    };
+    use serde_json::json;
+
+    use super::{RevertReason, ERROR_SELECTOR, PANIC_SELECTOR};
+
+    #[test]
+    fn decode() {
+        let data = [&ERROR_SELECTOR[..], &abi::encode(&[Token::String("too low".into())])].concat();
+        let reason = RevertReason::decode(&data, None).unwrap();
+        assert_eq!(RevertReason::Error("too low".to_string()), reason);
+        assert_eq!("too low", reason.to_string());
+
+        let data = [&PANIC_SELECTOR[..], &abi::encode(&[Token::Uint(0x11.into())])].concat();
+        let reason = RevertReason::decode(&data, None).unwrap();
+        assert_eq!(RevertReason::Panic(EthersU256::from(0x11)), reason);
+        assert_eq!("panic: arithmetic overflow or underflow (0x11)", reason.to_string());
+
+        let abi: Abi = serde_json::from_value(json!([{
+            "type": "error",
+            "name": "Insufficient",
+            "inputs": [{ "name": "needed", "type": "uint256" }]
+        }]))
+        .unwrap();
+        let selector = abi.errors().next().unwrap().signature();
+        let data = [&selector[..4], &abi::encode(&[Token::Uint(7.into())])].concat();
+        assert_eq!(None, RevertReason::decode(&data, None));
+        let reason = RevertReason::decode(&data, Some(&abi)).unwrap();
+        assert_eq!(
+            RevertReason::Custom {
+                name: "Insufficient".to_string(),
+                args: vec![Token::Uint(7.into())]
+            },
+            reason
+        );
+        assert_eq!("Insufficient(7)", reason.to_string());
+    }
+}
@@ -0,0 +1,148 @@
+//! Per-block account and storage changes, read from the changesets of the database.
+//!
+//! Reth stores the value of every account and storage slot from before each block in the
+//! `AccountChangeSets` and `StorageChangeSets` tables, so the changes of a block range are read
+//! without executing any block. The values after a block are read from the historical state of
+//! that block.
+
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use ethers::{
+    providers::Middleware,
+    types::{Address as EthersAddress, H256 as EthersH256, U256 as EthersU256},
+};
+use reth_db::{cursor::DbCursorRO, models::BlockNumberAddress, tables, transaction::DbTx};
+use reth_primitives::{Account, Address, BlockNumber, StorageEntry, B256, U256};
+use reth_provider::{AccountReader, DatabaseProviderFactory, StateProvider, StateProviderFactory};
+
+use crate::{
+    type_conversions::{ToEthers, ToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// The fields of an account stored in the state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountInfo {
+    pub nonce: u64,
+    pub balance: EthersU256,
+    pub code_hash: EthersH256,
+}
+
+/// An account before and after a block, `None` if the account doesn't exist.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountChange {
+    pub before: Option<AccountInfo>,
+    pub after: Option<AccountInfo>,
+}
+
+/// A storage slot before and after a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageChange {
+    pub slot: EthersH256,
+    pub before: EthersU256,
+    pub after: EthersU256,
+}
+
+/// The changes of an account in a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStateDiff {
+    pub block_number: BlockNumber,
+    pub address: EthersAddress,
+    /// The change of the account fields, `None` if only its storage changed.
+    pub account: Option<AccountChange>,
+    /// The changed storage slots, in slot order.
+    pub storage: Vec<StorageChange>,
+}
+
+/// The values before a block, as stored in the changesets.
+#[derive(Default)]
+struct ChangeSet {
+    account: Option<Option<Account>>,
+    storage: BTreeMap<B256, U256>,
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Returns the changes of `addresses` in every block of `range`, of all accounts if
+    /// `addresses` is empty.
+    ///
+    /// Diffs are ordered by block and address. Blocks whose changesets were pruned have no diffs.
+    pub fn state_diff(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        addresses: &[EthersAddress],
+    ) -> Result<Vec<AccountStateDiff>, RethMiddlewareError<M>> {
+        let addresses: Vec<Address> = addresses.iter().map(|address| address.into_reth()).collect();
+        let changesets = self.changesets(range, &addresses)?;
+
+        let mut diffs = Vec::new();
+        for (block_number, accounts) in changesets {
+            let state = self.reth_api.provider().history_by_block_number(block_number)?;
+
+            for (address, changeset) in accounts {
+                let account = match changeset.account {
+                    Some(before) => Some(AccountChange {
+                        before: before.map(|account| account.into_ethers()),
+                        after: state.basic_account(address)?.map(|account| account.into_ethers()),
+                    }),
+                    None => None,
+                };
+                let storage = changeset
+                    .storage
+                    .into_iter()
+                    .map(|(slot, before)| {
+                        let after = state.storage(address, slot)?.unwrap_or_default();
+                        Ok(StorageChange {
+                            slot: slot.into_ethers(),
+                            before: before.into_ethers(),
+                            after: after.into_ethers(),
+                        })
+                    })
+                    .collect::<Result<_, RethMiddlewareError<M>>>()?;
+
+                diffs.push(AccountStateDiff {
+                    block_number,
+                    address: address.into_ethers(),
+                    account,
+                    storage,
+                });
+            }
+        }
+
+        Ok(diffs)
+    }
+
+    /// Reads the account and storage changesets of `range`, keyed by block and address.
+    fn changesets(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        addresses: &[Address],
+    ) -> Result<BTreeMap<BlockNumber, BTreeMap<Address, ChangeSet>>, RethMiddlewareError<M>> {
+        let provider = self.reth_api.provider().database_provider_ro()?;
+        let tx = provider.tx_ref();
+        let wanted = |address: &Address| addresses.is_empty() || addresses.contains(address);
+        let mut changesets: BTreeMap<_, BTreeMap<_, ChangeSet>> = BTreeMap::new();
+
+        let mut accounts = tx.cursor_dup_read::<tables::AccountChangeSets>()?;
+        for entry in accounts.walk_range(range.clone())? {
+            let (block_number, before) = entry?;
+            if wanted(&before.address) {
+                let changeset = changesets.entry(block_number).or_default();
+                changeset.entry(before.address).or_default().account = Some(before.info);
+            }
+        }
+
+        let mut storages = tx.cursor_dup_read::<tables::StorageChangeSets>()?;
+        for entry in storages.walk_range(BlockNumberAddress::range(range))? {
+            let (BlockNumberAddress((block_number, address)), StorageEntry { key, value }) = entry?;
+            if wanted(&address) {
+                let changeset = changesets.entry(block_number).or_default();
+                changeset.entry(address).or_default().storage.insert(key, value);
+            }
+        }
+
+        Ok(changesets)
+    }
+}
@@ -0,0 +1,509 @@
+//! Enumeration of the storage of an account at a block.
+//!
+//! The latest storage is read from the `HashedStorages` table, ordered by the hash of the slots
+//! like `debug_storageRangeAt`, or from the `PlainStorageState` table, ordered by slot. The
+//! storage at an older block is the latest storage with the values from before the first later
+//! change of every slot, read from the `StorageChangeSets` table. The block of that change is
+//! found by seeking the shard of the `StoragesHistory` index that covers the block, once for every
+//! slot the account ever changed, so the cost of a query grows with the number of those slots, not
+//! with the number of their changes.
+//!
+//! `HashedStorages` only stores the hashes of the slots, the slots of a page are looked up in a
+//! map from hash to slot built once per query from the `PlainStorageState` table.
+
+use std::collections::{BTreeMap, HashMap};
+
+use ethers::{
+    providers::Middleware,
+    types::{
+        Address as EthersAddress, BlockId as EthersBlockId, H256 as EthersH256, U256 as EthersU256,
+    },
+};
+use futures::{stream, Stream, TryStreamExt};
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress},
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_primitives::{
+    keccak256, Address, BlockHashOrNumber, BlockId, BlockNumber, StorageEntry, B256, U256,
+};
+use reth_provider::{
+    BlockIdReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory, TransactionVariant,
+};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::{AccountState, CacheDB},
+};
+use reth_rpc::eth::{error::EthApiError, EthTransactions};
+
+use crate::{
+    inspect::{apply_pre_block_changes, replay},
+    type_conversions::{ToEthers, ToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// Number of slots [`RethMiddleware::dump_storage`] reads at once.
+pub const DEFAULT_PAGE_SIZE: usize = 1_000;
+
+/// A storage slot of a [`StorageRange`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageRangeEntry {
+    /// The slot, the preimage of its key in [`StorageRange::storage`].
+    pub key: Option<EthersH256>,
+    pub value: EthersH256,
+}
+
+/// A page of the non-zero storage slots of an account, as returned by `debug_storageRangeAt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageRange {
+    /// The slots by the hash of the slot.
+    pub storage: BTreeMap<EthersH256, StorageRangeEntry>,
+    /// The hash of the first slot of the next page, `None` on the last page.
+    pub next_key: Option<EthersH256>,
+}
+
+/// The storage of an account on top of the storage at the end of a block.
+struct StorageOverlay {
+    block_number: BlockNumber,
+    /// The slots changed by the executed transactions of the next block.
+    slots: BTreeMap<B256, U256>,
+    /// `true` if the account was created or destroyed by the executed transactions, so the
+    /// storage at the end of the block doesn't apply.
+    cleared: bool,
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Returns up to `max_result` non-zero storage slots of `address` whose hash is at least
+    /// `key_start`, after the first `tx_index` transactions of `block`, like
+    /// `debug_storageRangeAt`.
+    ///
+    /// Pass the returned `next_key` as `key_start` to read the next page.
+    pub async fn get_storage_range<T: Into<EthersBlockId>>(
+        &self,
+        block: T,
+        tx_index: usize,
+        address: EthersAddress,
+        key_start: EthersH256,
+        max_result: usize,
+    ) -> Result<StorageRange, RethMiddlewareError<M>> {
+        let address: Address = address.into_reth();
+        let overlay = self.storage_overlay(block.into().into_reth(), tx_index, address).await?;
+        let provider = self.reth_api.provider().database_provider_ro()?;
+        let tx = provider.tx_ref();
+
+        let changes = hashed_storage_changes(tx, address, &overlay)?;
+        let preimages =
+            if overlay.cleared { HashMap::new() } else { storage_preimages(tx, address)? };
+        let (slots, next_key) = hashed_storage_page(
+            tx,
+            address,
+            &changes,
+            &preimages,
+            overlay.cleared,
+            key_start.into_reth(),
+            max_result,
+        )?;
+        let storage = slots
+            .into_iter()
+            .map(|(hashed_slot, (slot, value))| {
+                let entry = StorageRangeEntry {
+                    key: slot.map(|slot| slot.into_ethers()),
+                    value: B256::from(value).into_ethers(),
+                };
+                (hashed_slot.into_ethers(), entry)
+            })
+            .collect();
+
+        Ok(StorageRange { storage, next_key: next_key.map(|key| key.into_ethers()) })
+    }
+
+    /// Streams every non-zero storage slot of `address` at the end of `block`, in slot order.
+    ///
+    /// All slots are read in a single read transaction, so they are consistent even if the node
+    /// persists new blocks meanwhile. The transaction stays open until the stream is dropped.
+    pub fn dump_storage<T: Into<EthersBlockId>>(
+        &self,
+        address: EthersAddress,
+        block: T,
+    ) -> impl Stream<Item = Result<(EthersH256, EthersU256), RethMiddlewareError<M>>> + '_ {
+        let address: Address = address.into_reth();
+        let block_id: BlockId = block.into().into_reth();
+
+        stream::once(async move {
+            let block_number = self
+                .reth_api
+                .provider()
+                .block_number_for_id(block_id)?
+                .ok_or(EthApiError::UnknownBlockNumber)?;
+            let provider = self.reth_api.provider().database_provider_ro()?;
+            let reverts = storage_reverts(provider.tx_ref(), address, block_number)?;
+
+            let state = (provider, reverts, Some(B256::ZERO));
+            let slots = stream::try_unfold(state, move |(provider, reverts, start)| async move {
+                let Some(start) = start else { return Ok(None) };
+                let (slots, next) = plain_storage_page(
+                    provider.tx_ref(),
+                    address,
+                    &reverts,
+                    start,
+                    DEFAULT_PAGE_SIZE,
+                )?;
+                let slots = slots.into_iter().map(|(slot, value)| {
+                    let slot: EthersH256 = slot.into_ethers();
+                    let value: EthersU256 = value.into_ethers();
+                    Ok((slot, value))
+                });
+                let state = (provider, reverts, next);
+                Ok::<_, RethMiddlewareError<M>>(Some((stream::iter(slots), state)))
+            });
+            Ok::<_, RethMiddlewareError<M>>(slots.try_flatten())
+        })
+        .try_flatten()
+    }
+
+    /// Executes the first `tx_index` transactions of `block` and returns the storage slots of
+    /// `address` they changed, on top of the storage at the end of the previous block.
+    ///
+    /// Nothing is executed if `tx_index` covers all transactions of the block.
+    async fn storage_overlay(
+        &self,
+        block: BlockId,
+        tx_index: usize,
+        address: Address,
+    ) -> Result<StorageOverlay, RethMiddlewareError<M>> {
+        let provider = self.reth_api.provider();
+        let block_hash =
+            provider.block_hash_for_id(block)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let block = provider
+            .block_with_senders(BlockHashOrNumber::Hash(block_hash), TransactionVariant::WithHash)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+
+        if tx_index >= block.body.len() {
+            return Ok(StorageOverlay {
+                block_number: block.number,
+                slots: BTreeMap::new(),
+                cleared: false,
+            })
+        }
+
+        let (cfg, block_env, _) = self.reth_api.evm_env_at(block_hash.into()).await?;
+        let chain_spec = provider.chain_spec();
+        let block_number = block.number - 1;
+        let parent_hash = block.parent_hash;
+        let parent_beacon_block_root = block.parent_beacon_block_root;
+        let txs: Vec<_> = block.into_transactions_ecrecovered().take(tx_index).collect();
+
+        let account = self
+            .reth_api
+            .spawn_with_state_at_block(parent_hash.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                apply_pre_block_changes(
+                    &mut db,
+                    &chain_spec,
+                    &cfg,
+                    &block_env,
+                    parent_beacon_block_root,
+                )?;
+                replay(&mut db, &cfg, &block_env, txs)?;
+                Ok(db.accounts.remove(&address))
+            })
+            .await?;
+
+        Ok(match account {
+            Some(account) => StorageOverlay {
+                block_number,
+                slots: account
+                    .storage
+                    .into_iter()
+                    .map(|(slot, value)| (B256::from(slot), value))
+                    .collect(),
+                cleared: matches!(
+                    account.account_state,
+                    AccountState::NotExisting | AccountState::StorageCleared
+                ),
+            },
+            None => StorageOverlay { block_number, slots: BTreeMap::new(), cleared: false },
+        })
+    }
+}
+
+/// Reads the value from before the first change after `block_number` of every storage slot of
+/// `address` that changed since.
+///
+/// For every slot, the shard of the history index covering `block_number + 1` is sought and the
+/// other shards are skipped, the value is read from the changeset of the first block after
+/// `block_number` in that shard.
+fn storage_reverts(
+    tx: &impl DbTx,
+    address: Address,
+    block_number: BlockNumber,
+) -> Result<BTreeMap<B256, U256>, DatabaseError> {
+    let mut history = tx.cursor_read::<tables::StoragesHistory>()?;
+    let mut changesets = tx.cursor_dup_read::<tables::StorageChangeSets>()?;
+    let mut reverts = BTreeMap::new();
+
+    let mut entry = history.seek(StorageShardedKey::new(address, B256::ZERO, block_number + 1))?;
+    while let Some((key, shard)) = entry {
+        if key.address != address {
+            break
+        }
+        let slot = key.sharded_key.key;
+        let highest = key.sharded_key.highest_block_number;
+        if highest <= block_number {
+            // the first shard of the next slot ends too early, seek the shard covering the block
+            entry = history.seek(StorageShardedKey::new(address, slot, block_number + 1))?;
+            continue
+        }
+        let changed =
+            shard.iter(0).map(|number| number as BlockNumber).find(|number| *number > block_number);
+        if let Some(changed) = changed {
+            let before = changesets
+                .seek_by_key_subkey(BlockNumberAddress((changed, address)), slot)?
+                .filter(|entry| entry.key == slot);
+            if let Some(StorageEntry { value, .. }) = before {
+                reverts.insert(slot, value);
+            }
+        }
+        // the last shard of a slot ends at `u64::MAX`, the entry after it starts the next slot
+        if highest != u64::MAX {
+            history.seek(StorageShardedKey::new(address, slot, u64::MAX))?;
+        }
+        entry = history.next()?;
+    }
+
+    Ok(reverts)
+}
+
+/// Returns the values of the slots of `address` that differ between the end of the block of
+/// `overlay` and the latest storage, by the hash of the slot.
+#[allow(clippy::type_complexity)]
+fn hashed_storage_changes(
+    tx: &impl DbTx,
+    address: Address,
+    overlay: &StorageOverlay,
+) -> Result<BTreeMap<B256, (Option<B256>, U256)>, DatabaseError> {
+    let mut changes = if overlay.cleared {
+        BTreeMap::new()
+    } else {
+        storage_reverts(tx, address, overlay.block_number)?
+    };
+    changes.extend(&overlay.slots);
+
+    Ok(changes.into_iter().map(|(slot, value)| (keccak256(slot), (Some(slot), value))).collect())
+}
+
+/// Maps the hashes of the slots of `address` in the latest storage to the slots.
+fn storage_preimages(
+    tx: &impl DbTx,
+    address: Address,
+) -> Result<HashMap<B256, B256>, DatabaseError> {
+    let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+    cursor
+        .walk_dup(Some(address), None)?
+        .map(|entry| entry.map(|(_, StorageEntry { key, .. })| (keccak256(key), key)))
+        .collect()
+}
+
+/// Reads a page of the non-zero storage slots of `address` in the order of their hashes, starting
+/// at the hash `start`, with `changes` from [`hashed_storage_changes`] applied. The latest
+/// storage is ignored if it was `cleared`, the slots of the unchanged ones are looked up in
+/// `preimages` from [`storage_preimages`].
+///
+/// Returns the slots by hash, with the slot itself and its value, and the hash of the first slot
+/// of the next page.
+#[allow(clippy::type_complexity)]
+fn hashed_storage_page(
+    tx: &impl DbTx,
+    address: Address,
+    changes: &BTreeMap<B256, (Option<B256>, U256)>,
+    preimages: &HashMap<B256, B256>,
+    cleared: bool,
+    start: B256,
+    limit: usize,
+) -> Result<(Vec<(B256, (Option<B256>, U256))>, Option<B256>), DatabaseError> {
+    let mut cursor = tx.cursor_dup_read::<tables::HashedStorages>()?;
+    let base =
+        if cleared { None } else { Some(cursor.walk_dup(Some(keccak256(address)), Some(start))?) };
+    let base = base
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.map(|(_, entry)| (entry.key, (None, entry.value))));
+
+    let slots = merge_slots(base, changes.range(start..).map(|(hash, slot)| (*hash, *slot)));
+    let (mut slots, next) = page(slots, limit, |(_, value)| value.is_zero())?;
+
+    // the table only stores the hashes, look up the slots of those that weren't changed
+    for (hash, (slot, _)) in &mut slots {
+        if slot.is_none() {
+            *slot = preimages.get(hash).copied();
+        }
+    }
+
+    Ok((slots, next))
+}
+
+/// Reads a page of the non-zero storage slots of `address` at the block `reverts` were read for,
+/// in slot order, starting at the slot `start`.
+///
+/// Returns the slots with their values and the first slot of the next page.
+#[allow(clippy::type_complexity)]
+fn plain_storage_page(
+    tx: &impl DbTx,
+    address: Address,
+    reverts: &BTreeMap<B256, U256>,
+    start: B256,
+    limit: usize,
+) -> Result<(Vec<(B256, U256)>, Option<B256>), DatabaseError> {
+    let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+    let base = cursor
+        .walk_dup(Some(address), Some(start))?
+        .map(|entry| entry.map(|(_, entry)| (entry.key, entry.value)));
+
+    let slots = merge_slots(base, reverts.range(start..).map(|(slot, value)| (*slot, *value)));
+    page(slots, limit, |value| value.is_zero())
+}
+
+/// Merges two iterators of slots ordered by key, `changes` take precedence over `base`.
+fn merge_slots<V>(
+    base: impl Iterator<Item = Result<(B256, V), DatabaseError>>,
+    changes: impl Iterator<Item = (B256, V)>,
+) -> impl Iterator<Item = Result<(B256, V), DatabaseError>> {
+    let mut base = base.peekable();
+    let mut changes = changes.peekable();
+
+    std::iter::from_fn(move || {
+        let base_key = match base.peek() {
+            Some(Ok((key, _))) => Some(*key),
+            Some(Err(_)) => return base.next(),
+            None => None,
+        };
+        match (base_key, changes.peek()) {
+            (None, None) => None,
+            (Some(base_key), Some((key, _))) if base_key < *key => base.next(),
+            (Some(_), None) => base.next(),
+            (base_key, Some((key, _))) => {
+                if base_key == Some(*key) {
+                    base.next();
+                }
+                changes.next().map(Ok)
+            }
+        }
+    })
+}
+
+/// Takes up to `limit` slots that aren't zero, and returns them with the key of the next one.
+#[allow(clippy::type_complexity)]
+fn page<V>(
+    slots: impl Iterator<Item = Result<(B256, V), DatabaseError>>,
+    limit: usize,
+    is_zero: impl Fn(&V) -> bool,
+) -> Result<(Vec<(B256, V)>, Option<B256>), DatabaseError> {
+    let mut page = Vec::with_capacity(limit);
+    for slot in slots {
+        let (key, value) = slot?;
+        if is_zero(&value) {
+            continue
+        }
+        if page.len() == limit {
+            return Ok((page, Some(key)))
+        }
+        page.push((key, value));
+    }
+
+    Ok((page, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_db::{database::Database, transaction::DbTxMut, BlockNumberList, DatabaseEnvKind};
+
+    use super::*;
+    use crate::init::init_db;
+
+    fn slot(n: u8) -> B256 {
+        B256::with_last_byte(n)
+    }
+
+    fn entry(n: u8, value: u64) -> StorageEntry {
+        StorageEntry { key: slot(n), value: U256::from(value) }
+    }
+
+    /// Indexes `blocks` as the changes of `slot`, in a shard ending at `highest`.
+    fn index(tx: &impl DbTxMut, address: Address, n: u8, highest: BlockNumber, blocks: &[u64]) {
+        let shard = BlockNumberList::new(blocks).unwrap();
+        tx.put::<tables::StoragesHistory>(StorageShardedKey::new(address, slot(n), highest), shard)
+            .unwrap();
+    }
+
+    #[test]
+    fn plain_storage_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = init_db(dir.path().join("db"), DatabaseEnvKind::RW).unwrap();
+        let address = Address::with_last_byte(1);
+        let other = Address::with_last_byte(2);
+
+        let tx = db.tx_mut().unwrap();
+        // latest storage: slot 2 was cleared and slot 4 created in block 2
+        for entry in [entry(1, 10), entry(3, 30), entry(4, 40), entry(5, 50)] {
+            tx.put::<tables::PlainStorageState>(address, entry).unwrap();
+        }
+        // slot 3 changed in blocks 1 and 2, indexed in two shards
+        tx.put::<tables::StorageChangeSets>(BlockNumberAddress((1, address)), entry(3, 3)).unwrap();
+        tx.put::<tables::StorageChangeSets>(BlockNumberAddress((1, address)), entry(5, 5)).unwrap();
+        for changed in [entry(2, 20), entry(3, 33), entry(4, 0)] {
+            tx.put::<tables::StorageChangeSets>(BlockNumberAddress((2, address)), changed).unwrap();
+        }
+        tx.put::<tables::StorageChangeSets>(BlockNumberAddress((2, other)), entry(1, 1)).unwrap();
+        index(&tx, address, 2, u64::MAX, &[2]);
+        index(&tx, address, 3, 1, &[1]);
+        index(&tx, address, 3, u64::MAX, &[2]);
+        index(&tx, address, 4, u64::MAX, &[2]);
+        index(&tx, address, 5, u64::MAX, &[1]);
+        index(&tx, other, 1, u64::MAX, &[2]);
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let reverts = storage_reverts(&tx, address, 1).unwrap();
+        assert_eq!(
+            reverts,
+            BTreeMap::from([
+                (slot(2), U256::from(20)),
+                (slot(3), U256::from(33)),
+                (slot(4), U256::ZERO),
+            ])
+        );
+
+        // the reverted slot 2 sorts between the slots of the latest storage, slot 4 didn't exist
+        let (first, next) = plain_storage_page(&tx, address, &reverts, B256::ZERO, 2).unwrap();
+        assert_eq!(first, vec![(slot(1), U256::from(10)), (slot(2), U256::from(20))]);
+        assert_eq!(next, Some(slot(3)));
+
+        let (second, next) = plain_storage_page(&tx, address, &reverts, slot(3), 2).unwrap();
+        assert_eq!(second, vec![(slot(3), U256::from(33)), (slot(5), U256::from(50))]);
+        assert_eq!(next, None);
+
+        // the first shard of slot 3 covers block 0, its last shard is skipped
+        assert_eq!(
+            storage_reverts(&tx, address, 0).unwrap(),
+            BTreeMap::from([
+                (slot(2), U256::from(20)),
+                (slot(3), U256::from(3)),
+                (slot(4), U256::ZERO),
+                (slot(5), U256::from(5)),
+            ])
+        );
+
+        // nothing changed after the latest block
+        assert!(storage_reverts(&tx, address, 2).unwrap().is_empty());
+        let (latest, next) =
+            plain_storage_page(&tx, address, &BTreeMap::new(), B256::ZERO, 4).unwrap();
+        assert_eq!(latest.len(), 4);
+        assert_eq!(next, None);
+    }
+}
@@ -0,0 +1,134 @@
+//! `trace_filter` over block ranges too large to trace at once.
+//!
+//! The range of a [`TraceScanner`] is split into chunks that are traced by the embedded
+//! [`RethTrace`](crate::RethTrace) concurrently, `after` and `count` apply to the whole range.
+
+use ethers::{
+    providers::Middleware,
+    types::{Trace as EthersTrace, TraceFilter as EthersTraceFilter},
+};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use reth_provider::BlockNumReader;
+use reth_rpc_types::trace::filter::TraceFilter;
+
+use crate::{
+    type_conversions::{rpc::trace::trace_filter_block_range, TryToEthers, TryToReth},
+    RethMiddleware, RethMiddlewareError,
+};
+
+/// Default number of blocks traced at once.
+pub const DEFAULT_CHUNK_SIZE: u64 = 100;
+
+/// Largest number of blocks traced at once, reth's `trace_filter` rejects wider ranges.
+pub const MAX_CHUNK_SIZE: u64 = 100;
+
+/// Default number of chunks traced concurrently.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Streams the traces matching a filter, in order.
+///
+/// Created by [`RethMiddleware::scan_traces`].
+#[must_use = "scanners do nothing unless you stream them"]
+pub struct TraceScanner<'a, M> {
+    middleware: &'a RethMiddleware<M>,
+    filter: EthersTraceFilter,
+    chunk_size: u64,
+    concurrency: usize,
+}
+
+impl<'a, M> TraceScanner<'a, M>
+where
+    M: Middleware,
+{
+    /// Sets the number of blocks traced at once, clamped to `1..=`[`MAX_CHUNK_SIZE`].
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        self
+    }
+
+    /// Sets the number of chunks traced concurrently.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns the stream of traces.
+    ///
+    /// Chunks are traced concurrently but yielded in block order. No further chunks are traced
+    /// once `count` traces were yielded.
+    pub fn stream(self) -> impl Stream<Item = Result<EthersTrace, RethMiddlewareError<M>>> + 'a {
+        let Self { middleware, filter, chunk_size, concurrency } = self;
+
+        stream::once(async move {
+            let (chunks, after, count) = middleware.trace_chunks(filter, chunk_size)?;
+            let traces = stream::iter(chunks)
+                .map(move |chunk| middleware.chunk_traces(chunk))
+                .buffered(concurrency)
+                .map_ok(|traces| stream::iter(traces.into_iter().map(Ok)))
+                .try_flatten()
+                .skip(after)
+                .take(count);
+            Ok::<_, RethMiddlewareError<M>>(traces)
+        })
+        .try_flatten()
+    }
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Returns a [`TraceScanner`] for the traces matching `filter`.
+    ///
+    /// An unset `fromBlock` defaults to the genesis block, an unset `toBlock` to the best block.
+    pub fn scan_traces(&self, filter: &EthersTraceFilter) -> TraceScanner<'_, M> {
+        TraceScanner {
+            middleware: self,
+            filter: filter.clone(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Splits the block range of `filter` into filters of at most `chunk_size` blocks, and
+    /// returns them with the `after` and `count` of `filter`.
+    fn trace_chunks(
+        &self,
+        filter: EthersTraceFilter,
+        chunk_size: u64,
+    ) -> Result<(Vec<TraceFilter>, usize, usize), RethMiddlewareError<M>> {
+        let (from_block, to_block) = trace_filter_block_range(&filter)?;
+        let filter: TraceFilter = filter.try_into_reth()?;
+
+        let best = self.reth_api.provider().best_block_number()?;
+        let from = match from_block {
+            Some(block) => self.resolve_block_number(Some(block), best)?,
+            None => 0,
+        };
+        let to = self.resolve_block_number(to_block, best)?.min(best);
+
+        let after = filter.after.unwrap_or_default() as usize;
+        let count = filter.count.map_or(usize::MAX, |count| count as usize);
+
+        let chunks = (from..=to)
+            .step_by(chunk_size as usize)
+            .map(|start| TraceFilter {
+                from_block: Some(start),
+                to_block: Some(start.saturating_add(chunk_size - 1).min(to)),
+                after: None,
+                count: None,
+                ..filter.clone()
+            })
+            .collect();
+
+        Ok((chunks, after, count))
+    }
+
+    /// Returns the traces of a single chunk.
+    async fn chunk_traces(
+        &self,
+        filter: TraceFilter,
+    ) -> Result<Vec<EthersTrace>, RethMiddlewareError<M>> {
+        Ok(self.reth_trace.trace_filter(filter).await?.try_into_ethers()?)
+    }
+}
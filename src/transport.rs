@@ -0,0 +1,338 @@
+//! A JSON-RPC transport that serves filters and new head subscriptions from the embedded node.
+//!
+//! The [`FilterWatcher`]s and [`SubscriptionStream`]s returned by [`Middleware::watch_blocks`]
+//! and friends poll the transport of the provider at the bottom of a middleware stack, which is
+//! bound to the inner provider of a [`RethMiddleware`]. A middleware built with
+//! [`RethMiddlewareBuilder::build_with_transport`] wraps that transport in a [`RethTransport`],
+//! which answers the filter and `newHeads` subscription requests from the embedded node and
+//! forwards everything else, so these trait methods run in-process for every middleware of the
+//! stack.
+//!
+//! [`FilterWatcher`]: ethers::providers::FilterWatcher
+//! [`SubscriptionStream`]: ethers::providers::SubscriptionStream
+//! [`Middleware::watch_blocks`]: ethers::providers::Middleware::watch_blocks
+//! [`RethMiddleware`]: crate::RethMiddleware
+//! [`RethMiddlewareBuilder::build_with_transport`]:
+//!     crate::builder::RethMiddlewareBuilder::build_with_transport
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use async_trait::async_trait;
+use ethers::{
+    providers::{
+        FilterKind, FilterWatcher, JsonRpcClient, JsonRpcError, Middleware, ProviderError,
+        PubsubClient, RpcError,
+    },
+    types::U256 as EthersU256,
+};
+use futures::{Stream, StreamExt};
+use jsonrpsee::types::ErrorObjectOwned;
+use reth_rpc_api::{EthApiServer, EthFilterApiServer};
+use reth_rpc_types::{Filter, FilterId};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{value::RawValue, Value};
+use thiserror::Error;
+
+use crate::{
+    type_conversions::{ConversionError, ToReth, TryToEthers},
+    watcher::BlockWatcher,
+    RethApi, RethFilter, RethMiddleware, RethMiddlewareError,
+};
+
+/// The notifications of a subscription.
+pub type NotificationStream = Pin<Box<dyn Stream<Item = Box<RawValue>> + Send>>;
+
+/// Wraps the transport `P` of the inner provider, see the [module docs](self).
+///
+/// Requests are forwarded to `P` until the middleware is built.
+pub struct RethTransport<P> {
+    inner: P,
+    node: EmbeddedNode,
+}
+
+impl<P> RethTransport<P> {
+    pub(crate) fn new(inner: P) -> Self {
+        Self { inner, node: EmbeddedNode::default() }
+    }
+
+    /// Returns the handle the middleware connects the transport to the embedded node with.
+    pub(crate) fn node(&self) -> EmbeddedNode {
+        self.node.clone()
+    }
+}
+
+impl<P: Debug> Debug for RethTransport<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RethTransport").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+/// The components of the embedded node a [`RethTransport`] serves requests from, set once the
+/// middleware is built.
+#[derive(Clone, Default)]
+pub(crate) struct EmbeddedNode(Arc<OnceLock<NodeState>>);
+
+struct NodeState {
+    api: RethApi,
+    filter: RethFilter,
+    /// The `newHeads` subscriptions, their notifications until [`PubsubClient::subscribe`]
+    /// takes them.
+    subscriptions: Mutex<HashMap<EthersU256, Option<NotificationStream>>>,
+    next_subscription: AtomicU64,
+}
+
+impl EmbeddedNode {
+    /// Serves the requests of the transport from `api` and `filter` from now on.
+    pub(crate) fn connect(&self, api: RethApi, filter: RethFilter) {
+        let state = NodeState {
+            api,
+            filter,
+            subscriptions: Mutex::default(),
+            next_subscription: AtomicU64::new(0),
+        };
+        // a transport belongs to a single middleware
+        let _ = self.0.set(state);
+    }
+}
+
+impl NodeState {
+    /// Serves `method` from the embedded node, or returns `None` if it must be forwarded.
+    async fn request<P: JsonRpcClient>(
+        &self,
+        method: &str,
+        params: &Value,
+    ) -> Option<Result<Value, RethTransportError<P>>> {
+        Some(match method {
+            "eth_newFilter" => self.new_filter(params).await,
+            "eth_newBlockFilter" => new_filter_id(self.filter.new_block_filter().await),
+            "eth_newPendingTransactionFilter" => {
+                new_filter_id(self.filter.new_pending_transaction_filter(None).await)
+            }
+            "eth_getFilterChanges" => self.filter_changes(params).await,
+            "eth_getFilterLogs" => self.filter_logs(params).await,
+            "eth_uninstallFilter" => self.uninstall_filter(params).await,
+            "eth_subscribe" if *params == serde_json::json!(["newHeads"]) => {
+                self.subscribe_new_heads().and_then(json)
+            }
+            "eth_unsubscribe" => {
+                let id = subscription_id(params)?;
+                json(self.subscriptions.lock().unwrap().remove(&id).is_some())
+            }
+            _ => return None,
+        })
+    }
+
+    async fn new_filter<P: JsonRpcClient>(
+        &self,
+        params: &Value,
+    ) -> Result<Value, RethTransportError<P>> {
+        let (filter,): (Filter,) = serde_json::from_value(params.clone())?;
+        new_filter_id(self.filter.new_filter(filter).await)
+    }
+
+    async fn filter_changes<P: JsonRpcClient>(
+        &self,
+        params: &Value,
+    ) -> Result<Value, RethTransportError<P>> {
+        json(self.filter.filter_changes(filter_id(params)?).await?)
+    }
+
+    async fn filter_logs<P: JsonRpcClient>(
+        &self,
+        params: &Value,
+    ) -> Result<Value, RethTransportError<P>> {
+        json(self.filter.filter_logs(filter_id(params)?).await?)
+    }
+
+    async fn uninstall_filter<P: JsonRpcClient>(
+        &self,
+        params: &Value,
+    ) -> Result<Value, RethTransportError<P>> {
+        json(self.filter.uninstall_filter(filter_id(params)?).await?)
+    }
+
+    /// Registers a subscription to the new blocks of the embedded node, whose notifications are
+    /// taken by [`PubsubClient::subscribe`].
+    fn subscribe_new_heads<P: JsonRpcClient>(&self) -> Result<EthersU256, RethTransportError<P>> {
+        let api = self.api.clone();
+        let blocks = BlockWatcher::new(api.provider().clone())?.filter_map(move |hash| {
+            let api = api.clone();
+            async move {
+                let block = api.block_by_hash(hash.into_reth(), false).await.ok().flatten()?;
+                serde_json::value::to_raw_value(&block).ok()
+            }
+        });
+
+        // the top bit keeps the ids apart from the ones of the inner transport
+        let id = EthersU256::from(self.next_subscription.fetch_add(1, Ordering::Relaxed)) |
+            (EthersU256::one() << 255);
+        self.subscriptions.lock().unwrap().insert(id, Some(Box::pin(blocks)));
+        Ok(id)
+    }
+}
+
+/// Encodes the id of a new filter as a quantity, like `RethMiddleware::new_filter` returns it.
+fn new_filter_id<P: JsonRpcClient>(
+    id: Result<FilterId, ErrorObjectOwned>,
+) -> Result<Value, RethTransportError<P>> {
+    let id: EthersU256 = id?.try_into_ethers()?;
+    json(id)
+}
+
+/// Reads the filter id of the params of a filter method.
+fn filter_id<P: JsonRpcClient>(params: &Value) -> Result<FilterId, RethTransportError<P>> {
+    let (id,): (EthersU256,) = serde_json::from_value(params.clone())?;
+    Ok(id.into_reth())
+}
+
+/// Reads the id of the params of `eth_unsubscribe`, if it is a subscription of the embedded node.
+fn subscription_id(params: &Value) -> Option<EthersU256> {
+    let (id,): (EthersU256,) = serde_json::from_value(params.clone()).ok()?;
+    id.bit(255).then_some(id)
+}
+
+fn json<P: JsonRpcClient, T: Serialize>(value: T) -> Result<Value, RethTransportError<P>> {
+    Ok(serde_json::to_value(value)?)
+}
+
+#[async_trait]
+impl<P> JsonRpcClient for RethTransport<P>
+where
+    P: JsonRpcClient + 'static,
+{
+    type Error = RethTransportError<P>;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let Some(node) = self.node.0.get() else {
+            return self.inner.request(method, params).await.map_err(RethTransportError::Inner)
+        };
+
+        let params = serde_json::to_value(params)?;
+        match node.request(method, &params).await {
+            Some(result) => Ok(serde_json::from_value(result?)?),
+            None => self.inner.request(method, params).await.map_err(RethTransportError::Inner),
+        }
+    }
+}
+
+impl<P> PubsubClient for RethTransport<P>
+where
+    P: PubsubClient + 'static,
+    P::NotificationStream: 'static,
+{
+    type NotificationStream = NotificationStream;
+
+    fn subscribe<T: Into<EthersU256>>(
+        &self,
+        id: T,
+    ) -> Result<Self::NotificationStream, Self::Error> {
+        let id = id.into();
+        if let Some(node) = self.node.0.get().filter(|_| id.bit(255)) {
+            return node
+                .subscriptions
+                .lock()
+                .unwrap()
+                .get_mut(&id)
+                .and_then(Option::take)
+                .ok_or(RethTransportError::UnknownSubscription(id))
+        }
+
+        Ok(Box::pin(self.inner.subscribe(id).map_err(RethTransportError::Inner)?))
+    }
+
+    fn unsubscribe<T: Into<EthersU256>>(&self, id: T) -> Result<(), Self::Error> {
+        let id = id.into();
+        if let Some(node) = self.node.0.get().filter(|_| id.bit(255)) {
+            node.subscriptions.lock().unwrap().remove(&id);
+            return Ok(())
+        }
+
+        self.inner.unsubscribe(id).map_err(RethTransportError::Inner)
+    }
+}
+
+impl<M: Middleware> RethMiddleware<M> {
+    /// Installs `filter` in the embedded node and watches it through the provider, or returns
+    /// `None` if the provider doesn't poll a [`RethTransport`] connected to this middleware.
+    pub(crate) async fn watch_filter<R>(
+        &self,
+        filter: FilterKind<'_>,
+    ) -> Option<Result<FilterWatcher<'_, M::Provider, R>, RethMiddlewareError<M>>>
+    where
+        R: Send + Sync + DeserializeOwned,
+    {
+        if !self.serves_transport {
+            return None
+        }
+
+        let provider = self.provider();
+        Some(
+            self.new_filter(filter)
+                .await
+                .map(|id| FilterWatcher::new(id, provider).interval(provider.get_interval())),
+        )
+    }
+}
+
+/// Errors of a [`RethTransport`].
+#[derive(Error, Debug)]
+pub enum RethTransportError<P: JsonRpcClient> {
+    /// An error of the inner transport.
+    #[error(transparent)]
+    Inner(P::Error),
+
+    /// An error of the embedded node.
+    #[error(transparent)]
+    Reth(#[from] ErrorObjectOwned),
+
+    #[error(transparent)]
+    Provider(#[from] reth_provider::ProviderError),
+
+    /// The notifications of a subscription of the embedded node were already taken.
+    #[error("Unknown subscription {0:#x}")]
+    UnknownSubscription(EthersU256),
+
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+impl<P: JsonRpcClient> RpcError for RethTransportError<P> {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            RethTransportError::Inner(err) => err.as_error_response(),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            RethTransportError::Inner(err) => err.as_serde_error(),
+            RethTransportError::SerdeJson(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<P: JsonRpcClient + 'static> From<RethTransportError<P>> for ProviderError {
+    fn from(err: RethTransportError<P>) -> Self {
+        match err {
+            RethTransportError::Inner(err) => err.into(),
+            err => ProviderError::JsonRpcClientError(Box::new(err)),
+        }
+    }
+}
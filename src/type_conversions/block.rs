@@ -12,9 +12,7 @@ impl ToReth<BlockId> for EthersBlockId {
             EthersBlockId::Hash(hash) => {
                 BlockId::Hash(<EthersH256 as ToReth<B256>>::into_reth(hash).into())
             }
-            EthersBlockId::Number(number) => {
-                BlockId::Number(BlockNumberOrTag::Number(number.as_number().unwrap().as_u64()))
-            }
+            EthersBlockId::Number(number) => BlockId::Number(number.into_reth()),
         }
     }
 }
@@ -3,12 +3,15 @@ use std::{
     hash::Hash,
 };
 
+use thiserror::Error;
+
 // Numerous acts of type terrorism having been commited during the making of this program. Please
 // forgive us.
 pub mod access_list;
 pub mod block;
 pub mod primitives;
 pub mod rpc;
+pub mod state;
 
 // -----------------------------------------------
 /// conversion traits
@@ -22,6 +25,93 @@ pub trait ToEthers<T> {
     fn into_ethers(self) -> T;
 }
 
+/// fallible conversion traits, for types that can't always be represented on the other side
+pub trait TryToReth<T> {
+    /// Ethers -> Reth
+    fn try_into_reth(self) -> Result<T, ConversionError>;
+}
+
+pub trait TryToEthers<T> {
+    /// Reth -> Ethers
+    fn try_into_ethers(self) -> Result<T, ConversionError>;
+}
+
+/// Errors that can occur in [`TryToReth`] and [`TryToEthers`] conversions.
+#[derive(Error, Debug)]
+pub enum ConversionError {
+    /// A field that is optional on one side but required on the other is missing, e.g. the
+    /// author of a pending block.
+    #[error("Missing field `{0}`")]
+    MissingField(&'static str),
+
+    /// The `v` value of a signature does not match its chain id.
+    #[error("Invalid signature v value {0}")]
+    InvalidSignatureV(u64),
+
+    /// Bytes that must have a fixed size, e.g. a blob or a KZG proof, have the wrong length.
+    #[error("Invalid length of `{field}`, expected {expected} bytes but got {len}")]
+    InvalidLength { field: &'static str, expected: usize, len: usize },
+
+    /// A filter id is not a hex quantity that fits into a `U256`.
+    #[error("Invalid filter id {0}")]
+    InvalidFilterId(String),
+
+    /// A value could not be converted through its JSON encoding.
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+// -----------------------------------------------
+/// generic fallible Vec<> conversion
+impl<T, F> TryToReth<Vec<T>> for Vec<F>
+where
+    F: TryToReth<T>,
+{
+    fn try_into_reth(self) -> Result<Vec<T>, ConversionError> {
+        self.into_iter().map(|x| x.try_into_reth()).collect()
+    }
+}
+
+impl<F, T> TryToEthers<Vec<F>> for Vec<T>
+where
+    T: TryToEthers<F>,
+{
+    fn try_into_ethers(self) -> Result<Vec<F>, ConversionError> {
+        self.into_iter().map(|x| x.try_into_ethers()).collect()
+    }
+}
+
+/// generic fallible Option<> conversion
+impl<T, F> TryToReth<Option<T>> for Option<F>
+where
+    F: TryToReth<T>,
+{
+    fn try_into_reth(self) -> Result<Option<T>, ConversionError> {
+        self.map(|x| x.try_into_reth()).transpose()
+    }
+}
+
+impl<F, T> TryToEthers<Option<F>> for Option<T>
+where
+    T: TryToEthers<F>,
+{
+    fn try_into_ethers(self) -> Result<Option<F>, ConversionError> {
+        self.map(|x| x.try_into_ethers()).transpose()
+    }
+}
+
+/// generic fallible BTreeMap<> conversion
+impl<T, K, F, U> TryToReth<BTreeMap<T, K>> for BTreeMap<F, U>
+where
+    F: ToReth<T>,
+    U: TryToReth<K>,
+    T: Ord,
+{
+    fn try_into_reth(self) -> Result<BTreeMap<T, K>, ConversionError> {
+        self.into_iter().map(|x| Ok((x.0.into_reth(), x.1.try_into_reth()?))).collect()
+    }
+}
+
 // -----------------------------------------------
 /// generic as_ref conversion
 impl<T, F> ToReth<F> for &T
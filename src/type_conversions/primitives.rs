@@ -1,12 +1,13 @@
-use crate::type_conversions::{ToEthers, ToReth};
-use std::{fmt::Debug, mem};
+use crate::type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers, TryToReth};
+use std::mem;
 
 use ethers::types::{
     Bloom as EthersBloom, Bytes as EthersBytes, H160 as EthersH160, H256 as EthersH256,
     H64 as EthersH64, U256 as EthersU256, U64 as EthersU64,
 };
 use reth_primitives::{
-    serde_helper::{num::U64HexOrNumber, JsonStorageKey}, Address, Bloom, Bytes, B256, B64, U128, U256, U64, U8
+    serde_helper::{num::U64HexOrNumber, JsonStorageKey},
+    Address, Bloom, Bytes, B256, B64, U128, U256, U64, U8,
 };
 
 /// non-Uint numerical conversions
@@ -20,7 +21,8 @@ macro_rules! impl_ToEthers {
                 let mut buf = [0u8; 2048];
 
                 buf[(2048-SIZE_IN)..].copy_from_slice(&Into::<[u8; SIZE_IN]>::into(self));
-                let nw = TryInto::<[u8; SIZE_OUT]>::try_into(&buf[(2048-SIZE_OUT)..]).unwrap();
+                let mut nw = [0u8; SIZE_OUT];
+                nw.copy_from_slice(&buf[(2048-SIZE_OUT)..]);
                 <$t>::from(nw)
             }
         }
@@ -29,22 +31,40 @@ macro_rules! impl_ToEthers {
     }
 }
 
-/// Uint<bits, limbs> numerical conversions
+/// Uint<bits, limbs> numerical conversions into an ethers type at least as wide
 #[macro_export]
 macro_rules! impl_ToEthers_Uint {
     ($t:ty, ($($u:ty),+)) => {
         $(impl ToEthers<$t> for $u {
             fn into_ethers(self) -> $t {
-                const SIZE_IN: usize = mem::size_of::<$u>();
+                const SIZE_IN: usize = <$u>::BYTES;
+                <$t>::from_big_endian(&self.to_be_bytes::<SIZE_IN>())
+            }
+        })*
+    }
+}
+
+/// Uint<bits, limbs> numerical conversions into a narrower ethers type, which fail if the value
+/// doesn't fit
+#[macro_export]
+macro_rules! impl_TryToEthers_Uint {
+    ($t:ty, ($($u:ty),+)) => {
+        $(impl TryToEthers<$t> for $u {
+            fn try_into_ethers(self) -> Result<$t, ConversionError> {
+                const SIZE_IN: usize = <$u>::BYTES;
                 const SIZE_OUT: usize = mem::size_of::<$t>();
-                let mut buf = [0u8; 64];
+                let len = self.bit_len().div_ceil(8);
+                if len > SIZE_OUT {
+                    return Err(ConversionError::InvalidLength {
+                        field: stringify!($t),
+                        expected: SIZE_OUT,
+                        len,
+                    })
+                }
 
-                buf[(64-SIZE_IN)..].copy_from_slice(&self.to_be_bytes::<SIZE_IN>());
-                <$t>::from_big_endian(&buf[(64-SIZE_OUT)..])
+                Ok(<$t>::from_big_endian(&self.to_be_bytes::<SIZE_IN>()[(SIZE_IN-SIZE_OUT)..]))
             }
-        }
-        impl_ToReth_Uint!($u, $t);
-    )*
+        })*
     }
 }
 
@@ -59,28 +79,56 @@ macro_rules! impl_ToReth {
                 let mut buf = [0u8; 2048];
 
                 buf[(2048-SIZE_IN)..].copy_from_slice(&Into::<[u8; SIZE_IN]>::into(self));
-                let nw = TryInto::<[u8; SIZE_OUT]>::try_into(&buf[(2048-SIZE_OUT)..]).unwrap();
+                let mut nw = [0u8; SIZE_OUT];
+                nw.copy_from_slice(&buf[(2048-SIZE_OUT)..]);
                 <$t>::from(nw)
             }
         })*
     }
 }
 
-/// Uint<bits, limbs> numerical conversions
+/// Uint<bits, limbs> numerical conversions into a reth type at least as wide
 #[macro_export]
 macro_rules! impl_ToReth_Uint {
     ($($t:ty, $u:ty),+) => {
         $(impl ToReth<$t> for $u {
             fn into_reth(self) -> $t {
                 const SIZE_IN: usize = mem::size_of::<$u>();
-                const SIZE_OUT: usize = mem::size_of::<$t>();
-                let mut buf = [0u8; 64];
+                const SIZE_OUT: usize = <$t>::BYTES;
+                let mut buf = [0u8; SIZE_OUT];
 
-                self.to_big_endian(&mut buf[(64-SIZE_IN)..]);
-                <$t>::try_from_be_slice(&buf[(64-SIZE_OUT)..]).unwrap()
+                self.to_big_endian(&mut buf[(SIZE_OUT-SIZE_IN)..]);
+                <$t>::from_be_bytes(buf)
             }
-        }
-    )*
+        })*
+    }
+}
+
+/// Uint<bits, limbs> numerical conversions into a narrower reth type, which fail if the value
+/// doesn't fit
+#[macro_export]
+macro_rules! impl_TryToReth_Uint {
+    ($($t:ty, $u:ty),+) => {
+        $(impl TryToReth<$t> for $u {
+            fn try_into_reth(self) -> Result<$t, ConversionError> {
+                const SIZE_IN: usize = mem::size_of::<$u>();
+                const SIZE_OUT: usize = <$t>::BYTES;
+                let len = self.bits().div_ceil(8);
+                if len > SIZE_OUT {
+                    return Err(ConversionError::InvalidLength {
+                        field: stringify!($t),
+                        expected: SIZE_OUT,
+                        len,
+                    })
+                }
+
+                let mut buf = [0u8; SIZE_IN];
+                self.to_big_endian(&mut buf);
+                let mut nw = [0u8; SIZE_OUT];
+                nw.copy_from_slice(&buf[(SIZE_IN-SIZE_OUT)..]);
+                Ok(<$t>::from_be_bytes(nw))
+            }
+        })*
     }
 }
 
@@ -92,28 +140,18 @@ macro_rules! array_impls {
             impl<T, F> ToReth<[T; $N]> for [F; $N]
             where
                 F: ToReth<T> + Clone,
-                T: Default + Debug,
             {
                 fn into_reth(self) -> [T; $N] {
-                    let mut result: [T; $N] = (0..$N).map(|i| self[i].clone().into_reth()).collect::<Vec<T>>().try_into().unwrap();
-                    for (i, item) in self.iter().enumerate() {
-                        result[i] = item.clone().into_reth();
-                    }
-                    result
+                    std::array::from_fn(|i| self[i].clone().into_reth())
                 }
             }
 
             impl<F, T> ToEthers<[F; $N]> for [T; $N]
             where
                 T: ToEthers<F> + Clone,
-                F: Default + Debug,
             {
                 fn into_ethers(self) -> [F; $N] {
-                    let mut result: [F; $N] = (0..$N).map(|i| self[i].clone().into_ethers()).collect::<Vec<F>>().try_into().unwrap();
-                    for (i, item) in self.iter().enumerate() {
-                        result[i] = item.clone().into_ethers();
-                    }
-                    result
+                    std::array::from_fn(|i| self[i].clone().into_ethers())
                 }
             }
         )+
@@ -122,43 +160,14 @@ macro_rules! array_impls {
 
 array_impls!(4, 32);
 
-impl_ToEthers_Uint!(EthersU256, (U256, U128));
-//impl_ToEthers!(EthersU256, (U64, B256));
-
-impl_ToEthers_Uint!(EthersU64, (U256));
-//impl_ToEthers!(EthersU64, (U64));
-
-impl ToEthers<EthersU64> for U64 {
-    fn into_ethers(self) -> EthersU64 {
-        let mut buf = [0u8; 8];
-        buf.copy_from_slice(&self.to_be_bytes::<8>());
-        EthersU64::from_big_endian(&buf)
-    }
-}
-
-impl ToReth<U64> for EthersU64 {
-    fn into_reth(self) -> U64 {
-        let mut buf = [0u8; 8];
-        self.to_big_endian(&mut buf);
-        U64::from_be_bytes(buf)
-    }
-}
-
-impl ToEthers<EthersU256> for U64 {
-    fn into_ethers(self) -> EthersU256 {
-        let mut buf = [0u8; 32];
-        buf[28..32].copy_from_slice(&self.to_be_bytes::<32>());
-        EthersU256::from_big_endian(&buf)
-    }
-}
+impl_ToEthers_Uint!(EthersU256, (U256, U128, U64));
+impl_ToReth_Uint!(U256, EthersU256);
+impl_TryToReth_Uint!(U128, EthersU256, U64, EthersU256);
 
-impl ToReth<U64> for EthersU256 {
-    fn into_reth(self) -> U64 {
-        let mut buf = [0u8; 32];
-        self.to_big_endian(&mut buf);
-        U64::from_be_bytes::<8>(buf[28..32].try_into().unwrap())
-    }
-}
+impl_ToEthers_Uint!(EthersU64, (U64, U8));
+impl_ToReth_Uint!(U256, EthersU64, U64, EthersU64);
+impl_TryToEthers_Uint!(EthersU64, (U256));
+impl_TryToReth_Uint!(U8, EthersU64);
 
 impl ToEthers<EthersU256> for B256 {
     fn into_ethers(self) -> EthersU256 {
@@ -196,30 +205,6 @@ impl_ToEthers!(EthersH64, (B64));
 
 impl_ToEthers!(EthersBloom, (Bloom));
 
-
-/// U8 (reth) -> U64 (ethers)
-impl ToReth<U8> for EthersU64 {
-    fn into_reth(self) -> U8 {
-        const SIZE_IN: usize = mem::size_of::<EthersU64>();
-        const SIZE_OUT: usize = 1;
-        let mut buf = [0u8; 64];
-
-        self.to_big_endian(&mut buf[(64 - SIZE_IN)..]);
-        <U8>::try_from_be_slice(&buf[(64 - SIZE_OUT)..]).unwrap()
-    }
-}
-
-impl ToEthers<EthersU64> for U8 {
-    fn into_ethers(self) -> EthersU64 {
-        const SIZE_IN: usize = 1;
-        const SIZE_OUT: usize = mem::size_of::<EthersU64>();
-        let mut buf = [0u8; 64];
-
-        buf[(64 - SIZE_IN)..].copy_from_slice(&self.to_be_bytes::<SIZE_IN>());
-        <EthersU64>::from_big_endian(&buf[(64 - SIZE_OUT)..])
-    }
-}
-
 /// Bytes conversion
 impl ToReth<Bytes> for EthersBytes {
     fn into_reth(self) -> Bytes {
@@ -247,15 +232,15 @@ impl ToEthers<B256> for JsonStorageKey {
     }
 }
 
-/// JsonStorageKey (reth) -> H256 (ethers)
-impl ToReth<U64HexOrNumber> for EthersU256 {
-    fn into_reth(self) -> U64HexOrNumber {
-        let u: U64 = self.into_reth();
-        U64HexOrNumber::from(u)
+/// U256 (ethers) -> U64HexOrNumber (reth)
+impl TryToReth<U64HexOrNumber> for EthersU256 {
+    fn try_into_reth(self) -> Result<U64HexOrNumber, ConversionError> {
+        let u: U64 = self.try_into_reth()?;
+        Ok(U64HexOrNumber::from(u))
     }
 }
 
-/// JsonStorageKey (ethers) -> H256 (reth)
+/// U64HexOrNumber (reth) -> U256 (ethers)
 impl ToEthers<EthersU256> for U64HexOrNumber {
     fn into_ethers(self) -> EthersU256 {
         let u: U64 = self.into();
@@ -267,7 +252,7 @@ impl ToEthers<EthersU256> for U64HexOrNumber {
 mod tests {
     use crate::type_conversions::*;
     use ethers::types::{Bloom as EthersBloom, U256 as EthersU256, U64 as EthersU64};
-    use reth_primitives::{hex_literal::hex, Bloom, U256, U64, U8};
+    use reth_primitives::{hex_literal::hex, Bloom, U128, U256, U64, U8};
 
     #[test]
     fn bloom() {
@@ -305,7 +290,7 @@ mod tests {
     fn ethers_u64_u8() {
         let r: U8 = U8::from(1);
         let e: EthersU64 = EthersU64::from(1);
-        assert_eq!(r, e.into_reth());
+        assert_eq!(r, e.try_into_reth().unwrap());
         assert_eq!(e, r.into_ethers());
     }
 
@@ -314,6 +299,36 @@ mod tests {
         let r: U256 = U256::from(1);
         let e: EthersU64 = EthersU64::from(1);
         assert_eq!(r, e.into_reth());
+        assert_eq!(e, r.try_into_ethers().unwrap());
+    }
+
+    #[test]
+    fn u64_ethers_u256() {
+        let r: U64 = U64::from(u64::MAX);
+        let e: EthersU256 = EthersU256::from(u64::MAX);
+        assert_eq!(r, e.try_into_reth().unwrap());
         assert_eq!(e, r.into_ethers());
     }
+
+    #[test]
+    fn truncating_conversions() {
+        let e = EthersU256::from(u64::MAX) + 1;
+        let r: Result<U64, _> = e.try_into_reth();
+        assert!(matches!(
+            r,
+            Err(ConversionError::InvalidLength { field: "U64", expected: 8, len: 9 })
+        ));
+
+        let r: Result<U128, _> = EthersU256::MAX.try_into_reth();
+        assert!(matches!(r, Err(ConversionError::InvalidLength { expected: 16, len: 32, .. })));
+
+        let r: Result<EthersU64, _> = (U256::from(u64::MAX) + U256::from(1)).try_into_ethers();
+        assert!(matches!(r, Err(ConversionError::InvalidLength { expected: 8, len: 9, .. })));
+
+        let r: Result<U8, _> = EthersU64::from(256).try_into_reth();
+        assert!(matches!(
+            r,
+            Err(ConversionError::InvalidLength { field: "U8", expected: 1, len: 2 })
+        ));
+    }
 }
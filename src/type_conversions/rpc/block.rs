@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-use crate::type_conversions::{ToEthers, ToReth};
+use crate::type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers, TryToReth};
 
 use ethers::types::{
     Block as EthersBlock, OtherFields as EthersOtherFields, Transaction as EthersTransaction, Withdrawal as EthersWithdrawal, H256 as EthersH256
@@ -8,19 +8,22 @@ use ethers::types::{
 use reth_rpc_types::{other::OtherFields, Block, BlockTransactions, Header, Rich, Withdrawal};
 
 /// EthersBlock<EthersH256> (ethers) -> Rich<Block> (reth)
-impl ToReth<Rich<Block>> for EthersBlock<EthersH256> {
-    fn into_reth(self) -> Rich<Block> {
+impl TryToReth<Rich<Block>> for EthersBlock<EthersH256> {
+    fn try_into_reth(self) -> Result<Rich<Block>, ConversionError> {
         let txs = self.transactions;
         let block = Block {
             header: Header {
                 hash: self.hash.into_reth(),
                 parent_hash: self.parent_hash.into_reth(),
                 uncles_hash: self.uncles_hash.into_reth(),
-                miner: self.author.into_reth().unwrap(),
+                miner: self.author.ok_or(ConversionError::MissingField("author"))?.into_reth(),
                 state_root: self.state_root.into_reth(),
                 transactions_root: self.transactions_root.into_reth(),
                 receipts_root: self.receipts_root.into_reth(),
-                logs_bloom: self.logs_bloom.into_reth().unwrap(),
+                logs_bloom: self
+                    .logs_bloom
+                    .ok_or(ConversionError::MissingField("logs_bloom"))?
+                    .into_reth(),
                 difficulty: self.difficulty.into_reth(),
                 number: self.number.into_reth(),
                 gas_limit: self.gas_limit.into_reth(),
@@ -31,8 +34,8 @@ impl ToReth<Rich<Block>> for EthersBlock<EthersH256> {
                 nonce: self.nonce.into_reth(),
                 base_fee_per_gas: self.base_fee_per_gas.into_reth(),
                 withdrawals_root: self.withdrawals_root.into_reth(),
-                blob_gas_used: self.blob_gas_used.into_reth(),
-                excess_blob_gas: self.excess_blob_gas.into_reth(),
+                blob_gas_used: self.blob_gas_used.try_into_reth()?,
+                excess_blob_gas: self.excess_blob_gas.try_into_reth()?,
                 parent_beacon_block_root: self.parent_beacon_block_root.into_reth(),
                 total_difficulty: self.total_difficulty.into_reth(),
             },
@@ -42,24 +45,27 @@ impl ToReth<Rich<Block>> for EthersBlock<EthersH256> {
             withdrawals: self.withdrawals.map(|w| w.into_reth()),
             other: OtherFields::default(),
         };
-        Rich::from(block)
+        Ok(Rich::from(block))
     }
 }
 
 /// EthersBlock<EthersTransaction> (ethers) -> Rich<Block> (reth)
-impl ToReth<Rich<Block>> for EthersBlock<EthersTransaction> {
-    fn into_reth(self) -> Rich<Block> {
+impl TryToReth<Rich<Block>> for EthersBlock<EthersTransaction> {
+    fn try_into_reth(self) -> Result<Rich<Block>, ConversionError> {
         let txs = self.transactions;
         let block = Block {
             header: Header {
                 hash: self.hash.into_reth(),
                 parent_hash: self.parent_hash.into_reth(),
                 uncles_hash: self.uncles_hash.into_reth(),
-                miner: self.author.into_reth().unwrap(),
+                miner: self.author.ok_or(ConversionError::MissingField("author"))?.into_reth(),
                 state_root: self.state_root.into_reth(),
                 transactions_root: self.transactions_root.into_reth(),
                 receipts_root: self.receipts_root.into_reth(),
-                logs_bloom: self.logs_bloom.into_reth().unwrap(),
+                logs_bloom: self
+                    .logs_bloom
+                    .ok_or(ConversionError::MissingField("logs_bloom"))?
+                    .into_reth(),
                 difficulty: self.difficulty.into_reth(),
                 number: self.number.into_reth(),
                 gas_limit: self.gas_limit.into_reth(),
@@ -70,31 +76,31 @@ impl ToReth<Rich<Block>> for EthersBlock<EthersTransaction> {
                 nonce: self.nonce.into_reth(),
                 base_fee_per_gas: self.base_fee_per_gas.into_reth(),
                 withdrawals_root: self.withdrawals_root.into_reth(),
-                blob_gas_used: self.blob_gas_used.into_reth(),
-                excess_blob_gas: self.excess_blob_gas.into_reth(),
+                blob_gas_used: self.blob_gas_used.try_into_reth()?,
+                excess_blob_gas: self.excess_blob_gas.try_into_reth()?,
                 parent_beacon_block_root: self.parent_beacon_block_root.into_reth(),
                 total_difficulty: self.total_difficulty.into_reth(),
             },
             uncles: self.uncles.into_reth(),
-            transactions: txs.into_reth(),
+            transactions: txs.try_into_reth()?,
             size: self.size.into_reth(),
             withdrawals: self.withdrawals.into_reth(),
             other: self.other.into_reth(),
         };
-        Rich::from(block)
+        Ok(Rich::from(block))
     }
 }
 
 // ---------------------------------------
 
-/// Rich<Block> (reth) -> EthersBlock<EthersTransaction> (ethers)
-impl ToEthers<EthersBlock<EthersH256>> for Rich<Block> {
-    fn into_ethers(self) -> EthersBlock<EthersH256> {
+/// Rich<Block> (reth) -> EthersBlock<EthersH256> (ethers)
+impl TryToEthers<EthersBlock<EthersH256>> for Rich<Block> {
+    fn try_into_ethers(self) -> Result<EthersBlock<EthersH256>, ConversionError> {
         let txs = match &self.transactions {
             BlockTransactions::Hashes(hashes) => hashes.into_ethers(),
             _ => vec![],
         };
-        EthersBlock {
+        Ok(EthersBlock {
             hash: self.header.hash.into_ethers(),
             parent_hash: self.header.parent_hash.into_ethers(),
             uncles_hash: self.header.uncles_hash.into_ethers(),
@@ -102,7 +108,7 @@ impl ToEthers<EthersBlock<EthersH256>> for Rich<Block> {
             state_root: self.header.state_root.into_ethers(),
             transactions_root: self.header.transactions_root.into_ethers(),
             receipts_root: self.header.receipts_root.into_ethers(),
-            number: self.header.number.into_ethers(),
+            number: self.header.number.try_into_ethers()?,
             gas_used: self.header.gas_used.into_ethers(),
             gas_limit: self.header.gas_limit.into_ethers(),
             extra_data: self.header.extra_data.clone().into_ethers(),
@@ -123,18 +129,18 @@ impl ToEthers<EthersBlock<EthersH256>> for Rich<Block> {
             transactions: txs,
             size: self.inner.size.into_ethers(),
             withdrawals: self.inner.withdrawals.into_ethers(),
-        }
+        })
     }
 }
 
 /// Rich<Block> (reth) -> EthersBlock<EthersTransaction> (ethers)
-impl ToEthers<EthersBlock<EthersTransaction>> for Rich<Block> {
-    fn into_ethers(self) -> EthersBlock<EthersTransaction> {
+impl TryToEthers<EthersBlock<EthersTransaction>> for Rich<Block> {
+    fn try_into_ethers(self) -> Result<EthersBlock<EthersTransaction>, ConversionError> {
         let txs = match &self.transactions {
-            BlockTransactions::Full(txs) => txs.into_ethers(),
+            BlockTransactions::Full(txs) => txs.clone().try_into_ethers()?,
             _ => vec![],
         };
-        EthersBlock {
+        Ok(EthersBlock {
             hash: self.header.hash.into_ethers(),
             parent_hash: self.header.parent_hash.into_ethers(),
             uncles_hash: self.header.uncles_hash.into_ethers(),
@@ -142,7 +148,7 @@ impl ToEthers<EthersBlock<EthersTransaction>> for Rich<Block> {
             state_root: self.header.state_root.into_ethers(),
             transactions_root: self.header.transactions_root.into_ethers(),
             receipts_root: self.header.receipts_root.into_ethers(),
-            number: self.header.number.into_ethers(),
+            number: self.header.number.try_into_ethers()?,
             gas_used: self.header.gas_used.into_ethers(),
             gas_limit: self.header.gas_limit.into_ethers(),
             extra_data: self.header.extra_data.clone().into_ethers(),
@@ -163,7 +169,7 @@ impl ToEthers<EthersBlock<EthersTransaction>> for Rich<Block> {
             size: self.inner.size.into_ethers(),
             withdrawals: self.inner.withdrawals.into_ethers(),
             other: self.inner.other.into_ethers(),
-        }
+        })
     }
 }
 
@@ -201,9 +207,9 @@ impl ToReth<OtherFields> for EthersOtherFields {
 
 impl ToEthers<EthersOtherFields> for OtherFields {
     fn into_ethers(self) -> EthersOtherFields {
-        let val = self.deref().clone();
-        let map = val.into_iter().collect();
-        serde_json::from_value(serde_json::Value::Object(map)).unwrap()
+        let mut other = EthersOtherFields::default();
+        other.extend(self.deref().clone());
+        other
     }
 }
 
@@ -215,8 +221,8 @@ impl ToReth<BlockTransactions> for Vec<EthersH256> {
 }
 
 /// EthersBlock<Transaction> (ethers) -> BlockTransactions (reth)
-impl ToReth<BlockTransactions> for Vec<EthersTransaction> {
-    fn into_reth(self) -> BlockTransactions {
-        BlockTransactions::Full(self.into_reth())
+impl TryToReth<BlockTransactions> for Vec<EthersTransaction> {
+    fn try_into_reth(self) -> Result<BlockTransactions, ConversionError> {
+        Ok(BlockTransactions::Full(self.try_into_reth()?))
     }
 }
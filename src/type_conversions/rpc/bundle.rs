@@ -0,0 +1,102 @@
+use crate::{
+    bundle::{CallBundleResponse, CallBundleTransactionResult},
+    type_conversions::ToEthers,
+};
+
+use reth_rpc_types::{EthCallBundleResponse, EthCallBundleTransactionResult};
+
+/// EthCallBundleResponse (reth) -> CallBundleResponse
+impl ToEthers<CallBundleResponse> for EthCallBundleResponse {
+    fn into_ethers(self) -> CallBundleResponse {
+        CallBundleResponse {
+            bundle_hash: self.bundle_hash.into_ethers(),
+            bundle_gas_price: self.bundle_gas_price.into_ethers(),
+            coinbase_diff: self.coinbase_diff.into_ethers(),
+            eth_sent_to_coinbase: self.eth_sent_to_coinbase.into_ethers(),
+            gas_fees: self.gas_fees.into_ethers(),
+            results: self.results.into_ethers(),
+            state_block_number: self.state_block_number,
+            total_gas_used: self.total_gas_used,
+        }
+    }
+}
+
+/// EthCallBundleTransactionResult (reth) -> CallBundleTransactionResult
+impl ToEthers<CallBundleTransactionResult> for EthCallBundleTransactionResult {
+    fn into_ethers(self) -> CallBundleTransactionResult {
+        CallBundleTransactionResult {
+            coinbase_diff: self.coinbase_diff.into_ethers(),
+            eth_sent_to_coinbase: self.eth_sent_to_coinbase.into_ethers(),
+            from_address: self.from_address.into_ethers(),
+            gas_fees: self.gas_fees.into_ethers(),
+            gas_price: self.gas_price.into_ethers(),
+            gas_used: self.gas_used,
+            to_address: self.to_address.into_ethers(),
+            tx_hash: self.tx_hash.into_ethers(),
+            value: self.value.into_ethers(),
+            revert: self.revert.into_ethers(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bundle::{CallBundleResponse, CallBundleTransactionResult},
+        type_conversions::ToEthers,
+    };
+
+    use ethers::types::{
+        Address as EthersAddress, Bytes as EthersBytes, H256 as EthersH256, U256 as EthersU256,
+    };
+    use reth_primitives::{Address, Bytes, B256, U256};
+    use reth_rpc_types::{EthCallBundleResponse, EthCallBundleTransactionResult};
+
+    #[test]
+    fn call_bundle_response() {
+        let r = EthCallBundleResponse {
+            bundle_hash: B256::with_last_byte(1),
+            bundle_gas_price: U256::from(2),
+            coinbase_diff: U256::from(42_000),
+            eth_sent_to_coinbase: U256::ZERO,
+            gas_fees: U256::from(42_000),
+            results: vec![EthCallBundleTransactionResult {
+                coinbase_diff: U256::from(42_000),
+                eth_sent_to_coinbase: U256::ZERO,
+                from_address: Address::with_last_byte(3),
+                gas_fees: U256::from(42_000),
+                gas_price: U256::from(2),
+                gas_used: 21_000,
+                to_address: Some(Address::with_last_byte(4)),
+                tx_hash: B256::with_last_byte(5),
+                value: Some(Bytes::new()),
+                revert: None,
+            }],
+            state_block_number: 6,
+            total_gas_used: 21_000,
+        };
+        let e = CallBundleResponse {
+            bundle_hash: EthersH256::from_low_u64_be(1),
+            bundle_gas_price: EthersU256::from(2),
+            coinbase_diff: EthersU256::from(42_000),
+            eth_sent_to_coinbase: EthersU256::zero(),
+            gas_fees: EthersU256::from(42_000),
+            results: vec![CallBundleTransactionResult {
+                coinbase_diff: EthersU256::from(42_000),
+                eth_sent_to_coinbase: EthersU256::zero(),
+                from_address: EthersAddress::from_low_u64_be(3),
+                gas_fees: EthersU256::from(42_000),
+                gas_price: EthersU256::from(2),
+                gas_used: 21_000,
+                to_address: Some(EthersAddress::from_low_u64_be(4)),
+                tx_hash: EthersH256::from_low_u64_be(5),
+                value: Some(EthersBytes::new()),
+                revert: None,
+            }],
+            state_block_number: 6,
+            total_gas_used: 21_000,
+        };
+
+        assert_eq!(e, r.into_ethers());
+    }
+}
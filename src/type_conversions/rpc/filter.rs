@@ -1,11 +1,11 @@
-use crate::type_conversions::{ToEthers, ToReth};
+use crate::type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers};
 
 use ethers::types::{
     Filter as EthersFilter, FilterBlockOption as EthersFilterBlockOption,
-    ValueOrArray as EthersValueOrArray, H256 as EthersH256,
+    ValueOrArray as EthersValueOrArray, H256 as EthersH256, U256 as EthersU256,
 };
 use reth_primitives::B256;
-use reth_rpc_types::{Filter, FilterBlockOption, ValueOrArray};
+use reth_rpc_types::{Filter, FilterBlockOption, FilterId, ValueOrArray};
 
 /// BlockNumber (ethers) -> BlockNumberOrTag (reth)
 impl ToReth<FilterBlockOption> for EthersFilterBlockOption {
@@ -95,3 +95,50 @@ impl ToEthers<EthersFilter> for Filter {
         }
     }
 }
+
+// -----------------------------------------------
+
+/// FilterId (ethers) -> (reth)
+///
+/// Reth hands out ids as hex quantities, which is also how the `U256` is formatted.
+impl ToReth<FilterId> for EthersU256 {
+    fn into_reth(self) -> FilterId {
+        FilterId::Str(format!("{self:#x}"))
+    }
+}
+
+/// FilterId (reth) -> (ethers)
+impl TryToEthers<EthersU256> for FilterId {
+    fn try_into_ethers(self) -> Result<EthersU256, ConversionError> {
+        match self {
+            FilterId::Num(id) => Ok(EthersU256::from(id)),
+            FilterId::Str(id) => id
+                .strip_prefix("0x")
+                .filter(|hex| !hex.is_empty())
+                .and_then(|hex| EthersU256::from_str_radix(hex, 16).ok())
+                .ok_or(ConversionError::InvalidFilterId(id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::type_conversions::{ConversionError, ToReth, TryToEthers};
+
+    use ethers::types::U256 as EthersU256;
+    use reth_rpc_types::FilterId;
+
+    #[test]
+    fn filter_id() {
+        let r = FilterId::Str("0x1f3a9c".to_string());
+        let e = EthersU256::from(0x1f3a9c);
+        assert_eq!(r, e.into_reth());
+        assert_eq!(e, r.try_into_ethers().unwrap());
+        assert_eq!(e, FilterId::Num(0x1f3a9c).try_into_ethers().unwrap());
+
+        for invalid in ["1f3a9c", "0xnope", "0x"] {
+            let r: Result<EthersU256, _> = FilterId::Str(invalid.to_string()).try_into_ethers();
+            assert!(matches!(r, Err(ConversionError::InvalidFilterId(id)) if id == invalid));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::type_conversions::{ToEthers, ToReth};
+use crate::type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers};
 
 use ethers::types::Log as EthersLog;
 use reth_rpc_types::Log;
@@ -21,20 +21,20 @@ impl ToReth<Log> for EthersLog {
 }
 
 /// Log (reth) -> (ethers)
-impl ToEthers<EthersLog> for Log {
-    fn into_ethers(self) -> EthersLog {
-        EthersLog {
+impl TryToEthers<EthersLog> for Log {
+    fn try_into_ethers(self) -> Result<EthersLog, ConversionError> {
+        Ok(EthersLog {
             address: self.address.into_ethers(),
             topics: self.topics.into_ethers(),
             data: self.data.into_ethers(),
             block_hash: self.block_hash.into_ethers(),
-            block_number: self.block_number.into_ethers(),
+            block_number: self.block_number.try_into_ethers()?,
             transaction_hash: self.transaction_hash.into_ethers(),
-            transaction_index: self.transaction_index.into_ethers(),
+            transaction_index: self.transaction_index.try_into_ethers()?,
             log_index: self.log_index.into_ethers(),
             transaction_log_index: None,
             log_type: None,
             removed: Some(self.removed),
-        }
+        })
     }
 }
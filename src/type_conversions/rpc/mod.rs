@@ -1,7 +1,10 @@
 pub mod account;
 pub mod block;
+pub mod bundle;
 pub mod fee;
 pub mod filter;
 pub mod log;
+pub mod overrides;
 pub mod trace;
-pub mod transaction;
\ No newline at end of file
+pub mod transaction;
+pub mod txpool;
\ No newline at end of file
@@ -0,0 +1,87 @@
+use crate::{
+    overrides::BlockOverrides as EthersBlockOverrides,
+    type_conversions::{ConversionError, ToReth, TryToReth},
+};
+
+use ethers::providers::spoof::State as EthersState;
+use reth_rpc_types::{state::StateOverride, BlockOverrides};
+
+/// State (ethers) -> StateOverride (reth)
+///
+/// The accounts of [`EthersState`] are private, both sides share the `eth_call` encoding.
+impl TryToReth<StateOverride> for EthersState {
+    fn try_into_reth(self) -> Result<StateOverride, ConversionError> {
+        Ok(serde_json::from_value(serde_json::to_value(self)?)?)
+    }
+}
+
+/// BlockOverrides -> (reth)
+impl ToReth<BlockOverrides> for EthersBlockOverrides {
+    fn into_reth(self) -> BlockOverrides {
+        BlockOverrides {
+            number: self.number.into_reth(),
+            difficulty: self.difficulty.into_reth(),
+            time: self.timestamp.into_reth(),
+            gas_limit: self.gas_limit.into_reth(),
+            coinbase: self.coinbase.into_reth(),
+            random: self.random.into_reth(),
+            base_fee: self.base_fee.into_reth(),
+            block_hash: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        overrides::BlockOverrides as EthersBlockOverrides,
+        type_conversions::{ToReth, TryToReth},
+    };
+
+    use ethers::{
+        providers::spoof,
+        types::{Address as EthersAddress, Bytes as EthersBytes, H256 as EthersH256},
+    };
+    use reth_primitives::{Address, Bytes, B256, U256, U64};
+    use reth_rpc_types::{state::StateOverride, BlockOverrides};
+
+    #[test]
+    fn state_override() {
+        let mut e = spoof::state();
+        e.account(EthersAddress::from_low_u64_be(1))
+            .balance(7.into())
+            .nonce(2.into())
+            .code(EthersBytes::from(vec![0x60, 0x00]))
+            .store(EthersH256::from_low_u64_be(3), EthersH256::from_low_u64_be(4));
+
+        let r: StateOverride = e.try_into_reth().unwrap();
+        let account = &r[&Address::with_last_byte(1)];
+
+        assert_eq!(account.balance, Some(U256::from(7)));
+        assert_eq!(account.nonce, Some(U64::from(2)));
+        assert_eq!(account.code, Some(Bytes::from(vec![0x60, 0x00])));
+        assert_eq!(account.state, None);
+        assert_eq!(
+            account.state_diff.as_ref().and_then(|diff| diff.get(&B256::with_last_byte(3))),
+            Some(&B256::with_last_byte(4))
+        );
+    }
+
+    #[test]
+    fn block_overrides() {
+        let e = EthersBlockOverrides::default()
+            .number(5)
+            .timestamp(6)
+            .base_fee(7)
+            .coinbase(EthersAddress::from_low_u64_be(8));
+        let r = BlockOverrides {
+            number: Some(U256::from(5)),
+            time: Some(U64::from(6)),
+            base_fee: Some(U256::from(7)),
+            coinbase: Some(Address::with_last_byte(8)),
+            ..Default::default()
+        };
+
+        assert_eq!(r, e.into_reth());
+    }
+}
@@ -1,8 +1,9 @@
-use crate::type_conversions::{ToEthers, ToReth};
+use crate::type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers, TryToReth};
 
 use ethers::types::{
     AccountDiff as EthersAccountDiff, AccountState as EthersAccountState, Action as EthersAction,
-    ActionType as EthersActionType, BlockTrace as EthersBlockTrace, Call as EthersCall,
+    ActionType as EthersActionType, Address as EthersAddress, BlockNumber as EthersBlockNumber,
+    BlockTrace as EthersBlockTrace, Call as EthersCall,
     CallFrame as EthersCallFrame, CallLogFrame as EthersCallLogFrame,
     CallResult as EthersCallResult, CallType as EthersCallType, ChangedType as EthersChangedType,
     Create as EthersCreate, CreateResult as EthersCreateResult, DefaultFrame as EthersDefaultFrame,
@@ -18,10 +19,12 @@ use ethers::types::{
     PreStateMode as EthersPreStateMode, Res as EthersRes, Reward as EthersReward,
     RewardType as EthersRewardType, StateDiff as EthersStateDiff, StorageDiff as EthersStorageDiff,
     StructLog as EthersStructLog, Suicide as EthersSuicide, Trace as EthersTrace,
-    TraceType as EthersTraceType, TransactionTrace as EthersTransactionTrace,
+    TraceFilter as EthersTraceFilter, TraceType as EthersTraceType,
+    TransactionTrace as EthersTransactionTrace,
     VMExecutedOperation as EthersVMExecutedOperation, VMOperation as EthersVMOperation,
     VMTrace as EthersVMTrace, U256,
 };
+use reth_primitives::BlockNumberOrTag;
 use reth_revm::primitives::bitvec::macros::internal::funty::Fundamental;
 use reth_rpc_types::trace::parity::{
     AccountDiff, Action, CallAction, CallOutput, CallType, ChangedType, CreateAction, CreateOutput,
@@ -30,28 +33,33 @@ use reth_rpc_types::trace::parity::{
     TransactionTrace, VmExecutedOperation, VmInstruction, VmTrace,
 };
 
-use reth_rpc_types::trace::geth::{
-    CallFrame, CallLogFrame, DefaultFrame, FourByteFrame, GethDebugBuiltInTracerType,
-    GethDebugTracerConfig, GethDebugTracerType, GethDebugTracingCallOptions,
-    GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace, NoopFrame, PreStateFrame,
-    StructLog,
+use reth_rpc_types::trace::{
+    common::TraceResult,
+    filter::{TraceFilter, TraceFilterMode},
+    geth::{
+        CallFrame, CallLogFrame, DefaultFrame, FourByteFrame, GethDebugBuiltInTracerType,
+        GethDebugTracerConfig, GethDebugTracerType, GethDebugTracingCallOptions,
+        GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace, NoopFrame, PreStateFrame,
+        StructLog,
+    },
 };
+use serde::Deserialize;
 
 /// GethDebugTracingCallOptions (ethers) -> (reth)
-impl ToReth<GethDebugTracingCallOptions> for EthersDebugTracingCallOptions {
-    fn into_reth(self) -> GethDebugTracingCallOptions {
-        GethDebugTracingCallOptions {
-            tracing_options: GethDebugTracingOptions::default(),
+impl TryToReth<GethDebugTracingCallOptions> for EthersDebugTracingCallOptions {
+    fn try_into_reth(self) -> Result<GethDebugTracingCallOptions, ConversionError> {
+        Ok(GethDebugTracingCallOptions {
+            tracing_options: self.tracing_options.try_into_reth()?,
             state_overrides: None,
             block_overrides: None,
-        }
+        })
     }
 }
 
 /// GethDebugTracingOptions (ethers) -> (reth)
-impl ToReth<GethDebugTracingOptions> for EthersDebugTracingOptions {
-    fn into_reth(self) -> GethDebugTracingOptions {
-        GethDebugTracingOptions {
+impl TryToReth<GethDebugTracingOptions> for EthersDebugTracingOptions {
+    fn try_into_reth(self) -> Result<GethDebugTracingOptions, ConversionError> {
+        Ok(GethDebugTracingOptions {
             config: GethDefaultTracingOptions {
                 enable_memory: self.enable_memory,
                 disable_memory: None,
@@ -64,12 +72,12 @@ impl ToReth<GethDebugTracingOptions> for EthersDebugTracingOptions {
             },
             tracer: self.tracer.into_reth(),
             tracer_config: if let Some(config) = self.tracer_config {
-                config.into_reth()
+                config.try_into_reth()?
             } else {
                 GethDebugTracerConfig::default()
             },
             timeout: self.timeout,
-        }
+        })
     }
 }
 
@@ -86,9 +94,9 @@ impl ToReth<GethDebugTracerType> for EthersGethDebugTracerType {
 }
 
 /// GethDebugTracerConfig (ethers) -> (reth)
-impl ToReth<GethDebugTracerConfig> for EthersGethDebugTracerConfig {
-    fn into_reth(self) -> GethDebugTracerConfig {
-        GethDebugTracerConfig(serde_json::to_value(self).unwrap())
+impl TryToReth<GethDebugTracerConfig> for EthersGethDebugTracerConfig {
+    fn try_into_reth(self) -> Result<GethDebugTracerConfig, ConversionError> {
+        Ok(GethDebugTracerConfig(serde_json::to_value(self)?))
     }
 }
 
@@ -250,9 +258,9 @@ impl ToReth<NoopFrame> for EthersNoopFrame {
 }
 
 /// GethTrace (reth) -> (ethers)
-impl ToEthers<EthersGethTrace> for GethTrace {
-    fn into_ethers(self) -> EthersGethTrace {
-        match self {
+impl TryToEthers<EthersGethTrace> for GethTrace {
+    fn try_into_ethers(self) -> Result<EthersGethTrace, ConversionError> {
+        Ok(match self {
             GethTrace::Default(frame) => {
                 EthersGethTrace::Known(EthersGethTraceFrame::Default(frame.into_ethers()))
             }
@@ -268,28 +276,44 @@ impl ToEthers<EthersGethTrace> for GethTrace {
             GethTrace::NoopTracer(frame) => {
                 EthersGethTrace::Known(EthersGethTraceFrame::NoopTracer(frame.into_ethers()))
             }
-            GethTrace::MuxTracer(_) => todo!(),
+            // ethers has no frame for the mux tracer
+            GethTrace::MuxTracer(frame) => EthersGethTrace::Unknown(serde_json::to_value(frame)?),
             GethTrace::JS(value) => EthersGethTrace::Unknown(value),
+        })
+    }
+}
+
+/// TraceResult (reth) -> GethTrace (ethers)
+///
+/// A failed transaction becomes `{"error": ..}`, which is how `debug_traceBlock*` reports it over
+/// JSON-RPC.
+impl TryToEthers<EthersGethTrace> for TraceResult<GethTrace, String> {
+    fn try_into_ethers(self) -> Result<EthersGethTrace, ConversionError> {
+        match self {
+            TraceResult::Success { result, .. } => result.try_into_ethers(),
+            TraceResult::Error { error, .. } => {
+                Ok(EthersGethTrace::Unknown(serde_json::json!({ "error": error })))
+            }
         }
     }
 }
 
 /// Action (ethers) -> (reth)
-impl ToReth<Action> for EthersAction {
-    fn into_reth(self) -> Action {
-        match self {
+impl TryToReth<Action> for EthersAction {
+    fn try_into_reth(self) -> Result<Action, ConversionError> {
+        Ok(match self {
             EthersAction::Call(a) => Action::Call(CallAction {
                 from: a.from.into_reth(),
                 to: a.to.into_reth(),
                 value: a.value.into_reth(),
-                gas: a.gas.into_reth(),
+                gas: a.gas.try_into_reth()?,
                 input: a.input.into_reth(),
                 call_type: a.call_type.into_reth(),
             }),
             EthersAction::Create(a) => Action::Create(CreateAction {
                 from: a.from.into_reth(),
                 value: a.value.into_reth(),
-                gas: a.gas.into_reth(),
+                gas: a.gas.try_into_reth()?,
                 init: a.init.into_reth(),
             }),
             EthersAction::Suicide(a) => Action::Selfdestruct(SelfdestructAction {
@@ -305,7 +329,7 @@ impl ToReth<Action> for EthersAction {
                     _ => RewardType::Block,
                 },
             }),
-        }
+        })
     }
 }
 
@@ -375,47 +399,98 @@ impl ToEthers<EthersCallType> for CallType {
 // -----------------------------------------------
 
 /// EthersTrace (ethers) -> TransactionTrace (reth)
-impl ToReth<TransactionTrace> for EthersTrace {
-    fn into_reth(self) -> TransactionTrace {
-        TransactionTrace {
+impl TryToReth<TransactionTrace> for EthersTrace {
+    fn try_into_reth(self) -> Result<TransactionTrace, ConversionError> {
+        Ok(TransactionTrace {
             trace_address: self.trace_address,
             subtraces: self.subtraces,
-            action: self.action.into_reth(),
-            result: match self.result.into_reth() {
+            action: self.action.try_into_reth()?,
+            result: match self.result.try_into_reth()? {
                 Some(Some(result)) => Some(result),
                 _ => None,
             },
             error: self.error,
-        }
+        })
+    }
+}
+
+/// The fields of ethers' `TraceFilter`, which are private.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFilterFields {
+    from_block: Option<EthersBlockNumber>,
+    to_block: Option<EthersBlockNumber>,
+    from_address: Option<Vec<EthersAddress>>,
+    to_address: Option<Vec<EthersAddress>>,
+    after: Option<u64>,
+    count: Option<u64>,
+}
+
+impl TryFrom<EthersTraceFilter> for TraceFilterFields {
+    type Error = ConversionError;
+
+    fn try_from(filter: EthersTraceFilter) -> Result<Self, Self::Error> {
+        Ok(serde_json::from_value(serde_json::to_value(filter)?)?)
+    }
+}
+
+/// Returns the `fromBlock` and `toBlock` of the filter, which may be tags unlike the block
+/// numbers of reth's `TraceFilter`.
+pub(crate) fn trace_filter_block_range(
+    filter: &EthersTraceFilter,
+) -> Result<(Option<BlockNumberOrTag>, Option<BlockNumberOrTag>), ConversionError> {
+    let fields = TraceFilterFields::try_from(filter.clone())?;
+    Ok((fields.from_block.into_reth(), fields.to_block.into_reth()))
+}
+
+/// TraceFilter (ethers) -> (reth)
+///
+/// Only block numbers are kept, tags are left unset. Traces match if they match the `fromAddress`
+/// or the `toAddress` list, like reth's `trace_filter` over RPC.
+impl TryToReth<TraceFilter> for EthersTraceFilter {
+    fn try_into_reth(self) -> Result<TraceFilter, ConversionError> {
+        let fields = TraceFilterFields::try_from(self)?;
+        Ok(TraceFilter {
+            from_block: fields.from_block.and_then(|block| block.as_number()).map(|n| n.as_u64()),
+            to_block: fields.to_block.and_then(|block| block.as_number()).map(|n| n.as_u64()),
+            from_address: fields.from_address.unwrap_or_default().into_reth(),
+            to_address: fields.to_address.unwrap_or_default().into_reth(),
+            mode: TraceFilterMode::Union,
+            after: fields.after,
+            count: fields.count,
+        })
     }
 }
 
 /// EthersTrace (ethers) + LocalizedTransactionTrace (reth)
-impl ToReth<LocalizedTransactionTrace> for EthersTrace {
-    fn into_reth(self) -> LocalizedTransactionTrace {
-        LocalizedTransactionTrace {
-            trace: self.clone().into_reth(),
+impl TryToReth<LocalizedTransactionTrace> for EthersTrace {
+    fn try_into_reth(self) -> Result<LocalizedTransactionTrace, ConversionError> {
+        Ok(LocalizedTransactionTrace {
+            trace: self.clone().try_into_reth()?,
             transaction_position: self.transaction_position.map(|x| x.as_u64()),
             transaction_hash: self.transaction_hash.into_reth(),
             block_number: Some(self.block_number),
             block_hash: Some(self.block_hash.into_reth()),
-        }
+        })
     }
 }
 
 /// LocalizedTransactionTrace (reth) -> EthersTrace (ethers)
-impl ToEthers<EthersTrace> for LocalizedTransactionTrace {
-    fn into_ethers(self) -> EthersTrace {
+impl TryToEthers<EthersTrace> for LocalizedTransactionTrace {
+    fn try_into_ethers(self) -> Result<EthersTrace, ConversionError> {
         let action = self.trace.action.into_ethers();
-        EthersTrace {
+        Ok(EthersTrace {
             action: action.clone(),
             result: self.trace.result.clone().into_ethers(),
             trace_address: self.trace.trace_address,
             subtraces: self.trace.subtraces,
             transaction_position: self.transaction_position.map(|x| x as usize),
             transaction_hash: self.transaction_hash.into_ethers(),
-            block_number: self.block_number.unwrap(),
-            block_hash: self.block_hash.into_ethers().unwrap(),
+            block_number: self.block_number.ok_or(ConversionError::MissingField("block_number"))?,
+            block_hash: self
+                .block_hash
+                .ok_or(ConversionError::MissingField("block_hash"))?
+                .into_ethers(),
             action_type: match action {
                 EthersAction::Call(_) => EthersActionType::Call,
                 EthersAction::Create(_) => EthersActionType::Create,
@@ -423,15 +498,15 @@ impl ToEthers<EthersTrace> for LocalizedTransactionTrace {
                 EthersAction::Reward(_) => EthersActionType::Reward,
             },
             error: self.trace.error,
-        }
+        })
     }
 }
 
 // -----------------------------------------------
 
-impl ToReth<CallOutput> for EthersCallResult {
-    fn into_reth(self) -> CallOutput {
-        CallOutput { gas_used: self.gas_used.into_reth(), output: self.output.into_reth() }
+impl TryToReth<CallOutput> for EthersCallResult {
+    fn try_into_reth(self) -> Result<CallOutput, ConversionError> {
+        Ok(CallOutput { gas_used: self.gas_used.try_into_reth()?, output: self.output.into_reth() })
     }
 }
 
@@ -446,13 +521,13 @@ impl ToEthers<EthersCallResult> for CallOutput {
 
 // -----------------------------------------------
 
-impl ToReth<CreateOutput> for EthersCreateResult {
-    fn into_reth(self) -> CreateOutput {
-        CreateOutput {
-            gas_used: self.gas_used.into_reth(),
+impl TryToReth<CreateOutput> for EthersCreateResult {
+    fn try_into_reth(self) -> Result<CreateOutput, ConversionError> {
+        Ok(CreateOutput {
+            gas_used: self.gas_used.try_into_reth()?,
             code: self.code.into_reth(),
             address: self.address.into_reth(),
-        }
+        })
     }
 }
 
@@ -468,13 +543,13 @@ impl ToEthers<EthersCreateResult> for CreateOutput {
 
 // -----------------------------------------------
 
-impl ToReth<Option<TraceOutput>> for EthersRes {
-    fn into_reth(self) -> Option<TraceOutput> {
-        match self {
-            EthersRes::Call(result) => Some(TraceOutput::Call(result.into_reth())),
-            EthersRes::Create(result) => Some(TraceOutput::Create(result.into_reth())),
+impl TryToReth<Option<TraceOutput>> for EthersRes {
+    fn try_into_reth(self) -> Result<Option<TraceOutput>, ConversionError> {
+        Ok(match self {
+            EthersRes::Call(result) => Some(TraceOutput::Call(result.try_into_reth()?)),
+            EthersRes::Create(result) => Some(TraceOutput::Create(result.try_into_reth()?)),
             EthersRes::None => None,
-        }
+        })
     }
 }
 
@@ -514,15 +589,15 @@ impl ToEthers<EthersTraceType> for TraceType {
 // -----------------------------------------------
 
 /// EthersBlockTrace (ethers) -> TraceResults (reth)
-impl ToReth<TraceResults> for EthersBlockTrace {
-    fn into_reth(self) -> TraceResults {
-        TraceResults {
+impl TryToReth<TraceResults> for EthersBlockTrace {
+    fn try_into_reth(self) -> Result<TraceResults, ConversionError> {
+        Ok(TraceResults {
             output: self.output.unwrap_or_default().into_reth(),
             // Ethers represents missing traces as Options, Reth uses empty vectors
-            trace: self.trace.unwrap_or_default().into_reth(),
+            trace: self.trace.unwrap_or_default().try_into_reth()?,
             vm_trace: self.vm_trace.into_reth(),
-            state_diff: self.state_diff.into_reth(),
-        }
+            state_diff: self.state_diff.try_into_reth()?,
+        })
     }
 }
 
@@ -548,12 +623,15 @@ impl ToEthers<EthersBlockTrace> for TraceResults {
 // -----------------------------------------------
 
 /// EthersBlockTrace (ethers) -> TraceResultsWithTransactionHash (reth)
-impl ToReth<TraceResultsWithTransactionHash> for EthersBlockTrace {
-    fn into_reth(self) -> TraceResultsWithTransactionHash {
-        TraceResultsWithTransactionHash {
-            full_trace: self.clone().into_reth(),
-            transaction_hash: self.transaction_hash.into_reth().unwrap(),
-        }
+impl TryToReth<TraceResultsWithTransactionHash> for EthersBlockTrace {
+    fn try_into_reth(self) -> Result<TraceResultsWithTransactionHash, ConversionError> {
+        Ok(TraceResultsWithTransactionHash {
+            full_trace: self.clone().try_into_reth()?,
+            transaction_hash: self
+                .transaction_hash
+                .ok_or(ConversionError::MissingField("transaction_hash"))?
+                .into_reth(),
+        })
     }
 }
 
@@ -579,18 +657,18 @@ impl ToEthers<EthersBlockTrace> for TraceResultsWithTransactionHash {
 // -----------------------------------------------
 
 /// TransactionTrace (ethers) -> (reth)
-impl ToReth<TransactionTrace> for EthersTransactionTrace {
-    fn into_reth(self) -> TransactionTrace {
-        TransactionTrace {
+impl TryToReth<TransactionTrace> for EthersTransactionTrace {
+    fn try_into_reth(self) -> Result<TransactionTrace, ConversionError> {
+        Ok(TransactionTrace {
             trace_address: self.trace_address,
             subtraces: self.subtraces,
-            action: self.action.into_reth(),
-            result: match self.result.into_reth() {
+            action: self.action.try_into_reth()?,
+            result: match self.result.try_into_reth()? {
                 Some(Some(result)) => Some(result),
                 _ => None,
             },
             error: self.error,
-        }
+        })
     }
 }
 
@@ -723,14 +801,14 @@ impl ToEthers<EthersStorageDiff> for StorageDelta {
 // -----------------------------------------------
 
 /// AccountDiff (ethers) -> (reth)
-impl ToReth<AccountDiff> for EthersAccountDiff {
-    fn into_reth(self) -> AccountDiff {
-        AccountDiff {
+impl TryToReth<AccountDiff> for EthersAccountDiff {
+    fn try_into_reth(self) -> Result<AccountDiff, ConversionError> {
+        Ok(AccountDiff {
             balance: self.balance.into_reth(),
-            nonce: self.nonce.into_reth(),
+            nonce: self.nonce.try_into_reth()?,
             code: self.code.into_reth(),
             storage: self.storage.into_reth(),
-        }
+        })
     }
 }
 
@@ -748,9 +826,9 @@ impl ToEthers<EthersAccountDiff> for AccountDiff {
 // -----------------------------------------------
 
 /// StateDiff (ethers) -> (reth)
-impl ToReth<StateDiff> for EthersStateDiff {
-    fn into_reth(self) -> StateDiff {
-        StateDiff(self.0.into_reth())
+impl TryToReth<StateDiff> for EthersStateDiff {
+    fn try_into_reth(self) -> Result<StateDiff, ConversionError> {
+        Ok(StateDiff(self.0.try_into_reth()?))
     }
 }
 
@@ -779,6 +857,22 @@ where
     }
 }
 
+/// Diff (ethers) -> Delta (reth), for values that may not fit
+impl<T, F> TryToReth<Delta<T>> for EthersDiff<F>
+where
+    F: TryToReth<T>,
+    T: Clone,
+{
+    fn try_into_reth(self) -> Result<Delta<T>, ConversionError> {
+        Ok(match self {
+            EthersDiff::Same => Delta::Unchanged,
+            EthersDiff::Born(x) => Delta::Added(x.try_into_reth()?),
+            EthersDiff::Died(x) => Delta::Removed(x.try_into_reth()?),
+            EthersDiff::Changed(x) => Delta::Changed(x.try_into_reth()?),
+        })
+    }
+}
+
 /// Delta (reth) -> Diff (ethers)
 impl<F, T> ToEthers<EthersDiff<F>> for Delta<T>
 where
@@ -808,6 +902,17 @@ where
     }
 }
 
+/// ChangedType (ethers) -> (reth), for values that may not fit
+impl<T, F> TryToReth<ChangedType<T>> for EthersChangedType<F>
+where
+    F: TryToReth<T>,
+    T: Clone,
+{
+    fn try_into_reth(self) -> Result<ChangedType<T>, ConversionError> {
+        Ok(ChangedType { from: self.from.try_into_reth()?, to: self.to.try_into_reth()? })
+    }
+}
+
 /// ChangedType (reth) -> (ethers)
 impl<F, T> ToEthers<EthersChangedType<F>> for ChangedType<T>
 where
@@ -1,24 +1,62 @@
-use crate::type_conversions::{ToEthers, ToReth};
+use crate::{
+    blob::{BlobSidecar, Eip4844TransactionRequest},
+    overrides::CallRequest,
+    type_conversions::{ConversionError, ToEthers, ToReth, TryToEthers, TryToReth},
+};
 
 use ethers::types::{
-    transaction::eip2718::TypedTransaction, NameOrAddress, OtherFields, Transaction as EthersTransaction, TransactionReceipt as EthersTransactionReceipt
+    transaction::eip2718::TypedTransaction, Bytes as EthersBytes, Eip1559TransactionRequest,
+    NameOrAddress, OtherFields as EthersOtherFields, Transaction as EthersTransaction,
+    TransactionReceipt as EthersTransactionReceipt,
 };
-use reth_primitives::{Signature as PrimitiveSignature, U256, U8};
+use reth_primitives::{Bytes, FixedBytes, Signature as PrimitiveSignature, U256, U8};
 use reth_revm::primitives::ruint::Uint;
-use reth_rpc_types::{AccessList, Parity, Signature, Transaction, TransactionReceipt, TransactionRequest};
+use reth_rpc_types::{
+    AccessList, BlobTransactionSidecar, Parity, Signature, Transaction, TransactionReceipt,
+    TransactionRequest,
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+/// Removes an EIP-4844 field that ethers only keeps in its `other` fields.
+fn take_other<T: DeserializeOwned>(
+    other: &mut EthersOtherFields,
+    key: &str,
+) -> Result<Option<T>, ConversionError> {
+    Ok(other.remove(key).map(serde_json::from_value).transpose()?)
+}
+
+/// Copies bytes into a fixed size array, e.g. a blob or a KZG commitment.
+fn fixed_bytes<const N: usize>(
+    field: &'static str,
+    bytes: &EthersBytes,
+) -> Result<FixedBytes<N>, ConversionError> {
+    if bytes.len() != N {
+        return Err(ConversionError::InvalidLength { field, expected: N, len: bytes.len() })
+    }
+    Ok(FixedBytes::from_slice(bytes))
+}
 
 /// Transaction (ethers) -> (reth)
-impl ToReth<Transaction> for EthersTransaction {
-    fn into_reth(self) -> Transaction {
+impl TryToReth<Transaction> for EthersTransaction {
+    fn try_into_reth(self) -> Result<Transaction, ConversionError> {
+        let mut other = self.other;
+        let max_fee_per_blob_gas = take_other(&mut other, "maxFeePerBlobGas")?;
+        let blob_versioned_hashes =
+            take_other(&mut other, "blobVersionedHashes")?.unwrap_or_default();
+
         let v = self.v.as_u64();
-        let normalized_v = if v > 1 {
-            v - self.chain_id.expect("Should not have unnormalized v without chain id").as_u64() * 2 -
-                35
-        } else {
-            v
+        let normalized_v = match (v, self.chain_id) {
+            (0 | 1, _) => v,
+            // pre EIP-155
+            (27 | 28, _) => v - 27,
+            (_, Some(chain_id)) => v.wrapping_sub(chain_id.as_u64() * 2 + 35),
+            (_, None) => return Err(ConversionError::InvalidSignatureV(v)),
         };
 
-        assert!((normalized_v == 0) | (normalized_v == 1));
+        if normalized_v > 1 {
+            return Err(ConversionError::InvalidSignatureV(v))
+        }
 
         let primitive_signature = PrimitiveSignature {
             r: self.r.into_reth(),
@@ -26,19 +64,19 @@ impl ToReth<Transaction> for EthersTransaction {
             odd_y_parity: normalized_v == 1,
         };
 
-        Transaction {
+        Ok(Transaction {
             hash: self.hash.into_reth(),
-            nonce: self.nonce.into_reth(),
+            nonce: self.nonce.try_into_reth()?,
             block_hash: self.block_hash.into_reth(),
             block_number: self.block_number.into_reth(),
             transaction_index: self.transaction_index.into_reth(),
             from: self.from.into_reth(),
             to: self.to.into_reth(),
             value: self.value.into_reth(),
-            gas_price: self.gas_price.into_reth(),
+            gas_price: self.gas_price.try_into_reth()?,
             gas: self.gas.into_reth(),
-            max_fee_per_gas: self.max_fee_per_gas.into_reth(),
-            max_priority_fee_per_gas: self.max_priority_fee_per_gas.into_reth(),
+            max_fee_per_gas: self.max_fee_per_gas.try_into_reth()?,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.try_into_reth()?,
             input: self.input.into_reth(),
             signature: Some(Signature {
                 r: primitive_signature.r,
@@ -48,35 +86,43 @@ impl ToReth<Transaction> for EthersTransaction {
                 ),
                 y_parity: Some(Parity(primitive_signature.odd_y_parity)),
             }),
-            chain_id: self.chain_id.into_reth(),
+            chain_id: self.chain_id.try_into_reth()?,
             access_list: self.access_list.map(|a| a.into_reth().0),
             transaction_type: self.transaction_type.into_reth(),
-            max_fee_per_blob_gas: None,
-            blob_versioned_hashes: vec![],
-            other: Default::default(),
-        }
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
+            other: other.into_reth(),
+        })
     }
 }
 
 /// Transaction (reth) -> (ethers)
-impl ToEthers<EthersTransaction> for Transaction {
-    fn into_ethers(self) -> EthersTransaction {
+impl TryToEthers<EthersTransaction> for Transaction {
+    fn try_into_ethers(self) -> Result<EthersTransaction, ConversionError> {
         let (v, r, s) =
             self.signature.map_or((Uint::MIN, Uint::MIN, Uint::MIN), |sig| (sig.v, sig.r, sig.s));
 
-        EthersTransaction {
+        let mut other: EthersOtherFields = self.other.into_ethers();
+        if let Some(max_fee_per_blob_gas) = self.max_fee_per_blob_gas {
+            other.insert("maxFeePerBlobGas".to_string(), json!(max_fee_per_blob_gas));
+        }
+        if !self.blob_versioned_hashes.is_empty() {
+            other.insert("blobVersionedHashes".to_string(), json!(self.blob_versioned_hashes));
+        }
+
+        Ok(EthersTransaction {
             hash: self.hash.into_ethers(),
             nonce: self.nonce.into_ethers(),
             block_hash: self.block_hash.into_ethers(),
-            block_number: self.block_number.into_ethers(),
-            transaction_index: self.transaction_index.into_ethers(),
+            block_number: self.block_number.try_into_ethers()?,
+            transaction_index: self.transaction_index.try_into_ethers()?,
             from: self.from.into_ethers(),
             to: self.to.into_ethers(),
             value: self.value.into_ethers(),
             gas_price: self.gas_price.into_ethers(),
             gas: self.gas.into_ethers(),
             input: self.input.into_ethers(),
-            v: v.into_ethers(),
+            v: v.try_into_ethers()?,
             r: r.into_ethers(),
             s: s.into_ethers(),
             transaction_type: self.transaction_type.into_ethers(),
@@ -84,13 +130,14 @@ impl ToEthers<EthersTransaction> for Transaction {
             max_priority_fee_per_gas: self.max_priority_fee_per_gas.into_ethers(),
             max_fee_per_gas: self.max_fee_per_gas.into_ethers(),
             chain_id: self.chain_id.into_ethers(),
-            ..Default::default()
-        }
+            other,
+        })
     }
 }
 
-impl ToReth<TransactionRequest> for TypedTransaction {
-    fn into_reth(self) -> TransactionRequest {
+/// TypedTransaction (ethers) -> TransactionRequest (reth)
+impl TryToReth<TransactionRequest> for TypedTransaction {
+    fn try_into_reth(self) -> Result<TransactionRequest, ConversionError> {
         let to = if let Some(addr) = self.to() {
             match addr {
                 NameOrAddress::Address(addr) => Some(addr.into_reth()),
@@ -104,37 +151,171 @@ impl ToReth<TransactionRequest> for TypedTransaction {
             TypedTransaction::Eip2930(_) => U8::from(1),
             TypedTransaction::Eip1559(_) => U8::from(2),
         };
-        TransactionRequest {
+        Ok(TransactionRequest {
             from: self.from().into_reth(),
             to: to,
             input: self.data().into_reth().unwrap_or_default().into(),
-            gas_price: self.gas_price().into_reth(),
+            gas_price: self.gas_price().try_into_reth()?,
             gas: self.gas().into_reth(),
             value: self.value().into_reth(),
-            nonce: self.nonce().into_reth(),
+            nonce: self.nonce().copied().try_into_reth()?,
             chain_id: self.chain_id().into_reth(),
-            max_fee_per_gas: self.as_eip1559_ref().map(|tx| tx.max_fee_per_gas.into_reth()).flatten(),
-            max_priority_fee_per_gas: self.as_eip1559_ref().map(|tx| tx.max_priority_fee_per_gas.into_reth()).flatten(),
+            max_fee_per_gas: self
+                .as_eip1559_ref()
+                .and_then(|tx| tx.max_fee_per_gas)
+                .try_into_reth()?,
+            max_priority_fee_per_gas: self
+                .as_eip1559_ref()
+                .and_then(|tx| tx.max_priority_fee_per_gas)
+                .try_into_reth()?,
             transaction_type: tx_type.into(),
             access_list: self.access_list().into_reth(),
-            max_fee_per_blob_gas: None,  // @todo
-            blob_versioned_hashes: None, // @todo
-            sidecar: Default::default(), // @todo
-            other: Default::default(),   // @todo
+            // ethers has no type 3 variant and its requests have no `other` fields to carry the
+            // blob fields in, blob transactions are converted from `CallRequest::Blob`
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
+            sidecar: None,
+            other: Default::default(),
+        })
+    }
+}
 
+/// CallRequest -> TransactionRequest (reth)
+impl TryToReth<TransactionRequest> for CallRequest {
+    fn try_into_reth(self) -> Result<TransactionRequest, ConversionError> {
+        match self {
+            CallRequest::Typed(tx) => tx.try_into_reth(),
+            CallRequest::Blob(tx) => tx.try_into_reth(),
         }
     }
+}
+
+/// Eip4844TransactionRequest -> TransactionRequest (reth)
+impl TryToReth<TransactionRequest> for Eip4844TransactionRequest {
+    fn try_into_reth(self) -> Result<TransactionRequest, ConversionError> {
+        let request: TransactionRequest = TypedTransaction::Eip1559(self.tx).try_into_reth()?;
+        Ok(TransactionRequest {
+            transaction_type: Some(U8::from(3)),
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas.try_into_reth()?,
+            blob_versioned_hashes: Some(self.blob_versioned_hashes.into_reth()),
+            sidecar: self.sidecar.try_into_reth()?,
+            ..request
+        })
+    }
+}
+
+/// TransactionRequest (reth) -> Eip4844TransactionRequest
+impl TryToEthers<Eip4844TransactionRequest> for TransactionRequest {
+    fn try_into_ethers(self) -> Result<Eip4844TransactionRequest, ConversionError> {
+        let blob_versioned_hashes = self
+            .blob_versioned_hashes
+            .ok_or(ConversionError::MissingField("blob_versioned_hashes"))?;
+        let tx = Eip1559TransactionRequest {
+            from: self.from.into_ethers(),
+            to: self.to.map(|to| NameOrAddress::Address(to.into_ethers())),
+            gas: self.gas.into_ethers(),
+            value: self.value.into_ethers(),
+            data: self.input.into_input().into_ethers(),
+            nonce: self.nonce.into_ethers(),
+            access_list: self.access_list.map(|list| list.into_ethers()).unwrap_or_default(),
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.into_ethers(),
+            max_fee_per_gas: self.max_fee_per_gas.into_ethers(),
+            chain_id: self.chain_id.into_ethers(),
+        };
+
+        Ok(Eip4844TransactionRequest {
+            tx,
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas.into_ethers(),
+            blob_versioned_hashes: blob_versioned_hashes.into_ethers(),
+            sidecar: self.sidecar.into_ethers(),
+        })
+    }
+}
+
+/// Transaction (ethers) -> TransactionRequest (reth), to execute a transaction again
+///
+/// The EIP-4844 fields are read from the `other` fields of the transaction.
+impl TryToReth<TransactionRequest> for EthersTransaction {
+    fn try_into_reth(self) -> Result<TransactionRequest, ConversionError> {
+        let mut other = self.other;
+        let max_fee_per_blob_gas = take_other(&mut other, "maxFeePerBlobGas")?;
+        let blob_versioned_hashes = take_other(&mut other, "blobVersionedHashes")?;
+        let input: Bytes = self.input.into_reth();
 
+        Ok(TransactionRequest {
+            from: Some(self.from.into_reth()),
+            to: self.to.into_reth(),
+            // fee market transactions report their effective gas price, which isn't part of them
+            gas_price: if self.max_fee_per_gas.is_none() {
+                self.gas_price.try_into_reth()?
+            } else {
+                None
+            },
+            max_fee_per_gas: self.max_fee_per_gas.try_into_reth()?,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.try_into_reth()?,
+            max_fee_per_blob_gas,
+            gas: Some(self.gas.into_reth()),
+            value: Some(self.value.into_reth()),
+            input: input.into(),
+            nonce: Some(self.nonce.try_into_reth()?),
+            chain_id: self.chain_id.try_into_reth()?,
+            access_list: self.access_list.into_reth(),
+            transaction_type: self.transaction_type.try_into_reth()?,
+            blob_versioned_hashes,
+            sidecar: None,
+            other: Default::default(),
+        })
+    }
 }
 
-// }
+/// BlobSidecar -> BlobTransactionSidecar (reth)
+impl TryToReth<BlobTransactionSidecar> for BlobSidecar {
+    fn try_into_reth(self) -> Result<BlobTransactionSidecar, ConversionError> {
+        Ok(BlobTransactionSidecar {
+            blobs: self
+                .blobs
+                .iter()
+                .map(|blob| fixed_bytes("blobs", blob))
+                .collect::<Result<_, _>>()?,
+            commitments: self
+                .commitments
+                .iter()
+                .map(|commitment| fixed_bytes("commitments", commitment))
+                .collect::<Result<_, _>>()?,
+            proofs: self
+                .proofs
+                .iter()
+                .map(|proof| fixed_bytes("proofs", proof))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// BlobTransactionSidecar (reth) -> BlobSidecar
+impl ToEthers<BlobSidecar> for BlobTransactionSidecar {
+    fn into_ethers(self) -> BlobSidecar {
+        BlobSidecar {
+            blobs: self.blobs.iter().map(|blob| EthersBytes::from(blob.to_vec())).collect(),
+            commitments: self
+                .commitments
+                .iter()
+                .map(|commitment| EthersBytes::from(commitment.to_vec()))
+                .collect(),
+            proofs: self.proofs.iter().map(|proof| EthersBytes::from(proof.to_vec())).collect(),
+        }
+    }
+}
 
 // -----------------------------------------------
 
 /// TransactionReceipt (ethers) -> (reth)
-impl ToReth<TransactionReceipt> for EthersTransactionReceipt {
-    fn into_reth(self) -> TransactionReceipt {
-        TransactionReceipt {
+impl TryToReth<TransactionReceipt> for EthersTransactionReceipt {
+    fn try_into_reth(self) -> Result<TransactionReceipt, ConversionError> {
+        let mut other = self.other;
+        let blob_gas_used = take_other(&mut other, "blobGasUsed")?;
+        let blob_gas_price = take_other(&mut other, "blobGasPrice")?;
+
+        Ok(TransactionReceipt {
             transaction_hash: Some(self.transaction_hash.into_reth()),
             transaction_index: self.transaction_index.into_reth(),
             block_hash: self.block_hash.into_reth(),
@@ -148,36 +329,53 @@ impl ToReth<TransactionReceipt> for EthersTransactionReceipt {
             status_code: self.status.into_reth(),
             state_root: self.root.into_reth(),
             logs_bloom: self.logs_bloom.into_reth(),
-            transaction_type: self.transaction_type.into_reth().unwrap(),
-            effective_gas_price: self.effective_gas_price.into_reth().unwrap(),
-            blob_gas_used: None,
-            blob_gas_price: None,
-            other: Default::default(),
-        }
+            transaction_type: self
+                .transaction_type
+                .ok_or(ConversionError::MissingField("transaction_type"))?
+                .try_into_reth()?,
+            effective_gas_price: self
+                .effective_gas_price
+                .ok_or(ConversionError::MissingField("effective_gas_price"))?
+                .try_into_reth()?,
+            blob_gas_used,
+            blob_gas_price,
+            other: other.into_reth(),
+        })
     }
 }
 
 /// TransactionReceipt (reth) -> (ethers)
-impl ToEthers<EthersTransactionReceipt> for TransactionReceipt {
-    fn into_ethers(self) -> EthersTransactionReceipt {
-        EthersTransactionReceipt {
-            transaction_hash: self.transaction_hash.into_ethers().unwrap(),
+impl TryToEthers<EthersTransactionReceipt> for TransactionReceipt {
+    fn try_into_ethers(self) -> Result<EthersTransactionReceipt, ConversionError> {
+        let mut other: EthersOtherFields = self.other.into_ethers();
+        if let Some(blob_gas_used) = self.blob_gas_used {
+            other.insert("blobGasUsed".to_string(), serde_json::to_value(blob_gas_used)?);
+        }
+        if let Some(blob_gas_price) = self.blob_gas_price {
+            other.insert("blobGasPrice".to_string(), serde_json::to_value(blob_gas_price)?);
+        }
+
+        Ok(EthersTransactionReceipt {
+            transaction_hash: self
+                .transaction_hash
+                .ok_or(ConversionError::MissingField("transaction_hash"))?
+                .into_ethers(),
             transaction_index: self.transaction_index.into_ethers(),
             block_hash: self.block_hash.into_ethers(),
-            block_number: self.block_number.into_ethers(),
+            block_number: self.block_number.try_into_ethers()?,
             from: self.from.into_ethers(),
             to: self.to.into_ethers(),
             cumulative_gas_used: self.cumulative_gas_used.into_ethers(),
             gas_used: self.gas_used.into_ethers(),
             contract_address: self.contract_address.into_ethers(),
-            logs: self.logs.into_ethers(),
+            logs: self.logs.try_into_ethers()?,
             status: self.status_code.into_ethers(),
             root: self.state_root.into_ethers(),
             logs_bloom: self.logs_bloom.into_ethers(),
             transaction_type: Some(self.transaction_type.into_ethers()),
             effective_gas_price: Some(self.effective_gas_price.into_ethers()),
-            other: OtherFields::default(),
-        }
+            other,
+        })
     }
 }
 
@@ -185,16 +383,21 @@ impl ToEthers<EthersTransactionReceipt> for TransactionReceipt {
 mod tests {
     use std::str::FromStr;
 
-    use crate::type_conversions::{ToEthers, ToReth};
+    use crate::{
+        blob::{BlobSidecar, Eip4844TransactionRequest},
+        overrides::CallRequest,
+        type_conversions::{ConversionError, ToEthers, TryToEthers, TryToReth},
+    };
 
     use ethers::types::{
-        Bytes as EthersBytes, Transaction as EthersTransaction,
+        Bytes as EthersBytes, Eip1559TransactionRequest, Transaction as EthersTransaction,
         TransactionReceipt as EthersTransactionReceipt, H160 as EthersH160, H256 as EthersH256,
         U256 as EthersU256, U64 as EthersU64,
     };
 
     use reth_primitives::{alloy_primitives::U160, hex_literal::hex, Address, Bloom, Bytes, B256, U128, U256, U64, U8};
-    use reth_rpc_types::{Parity, Signature, Transaction, TransactionReceipt};
+    use reth_rpc_types::{Parity, Signature, Transaction, TransactionReceipt, TransactionRequest};
+    use serde_json::json;
 
     #[test]
     fn transaction() {
@@ -256,8 +459,8 @@ mod tests {
             other: Default::default(),
         };
 
-        assert_eq!(r, e.clone().into_reth());
-        assert_eq!(e, r.into_ethers());
+        assert_eq!(r, e.clone().try_into_reth().unwrap());
+        assert_eq!(e, r.try_into_ethers().unwrap());
     }
 
     #[test]
@@ -309,7 +512,151 @@ mod tests {
             other: Default::default(),
         };
 
-        assert_eq!(r, e.clone().into_reth());
-        assert_eq!(e, r.into_ethers());
+        assert_eq!(r, e.clone().try_into_reth().unwrap());
+        assert_eq!(e, r.try_into_ethers().unwrap());
+    }
+
+    #[test]
+    fn conversion_errors() {
+        let e = EthersTransaction { v: EthersU64::from(38), chain_id: None, ..Default::default() };
+        let r: Result<Transaction, _> = e.try_into_reth();
+        assert!(matches!(r, Err(ConversionError::InvalidSignatureV(38))));
+
+        let e = EthersTransactionReceipt::default();
+        let r: Result<TransactionReceipt, _> = e.try_into_reth();
+        assert!(matches!(r, Err(ConversionError::MissingField("transaction_type"))));
+    }
+
+    #[test]
+    fn blob_transaction() {
+        let r = Transaction {
+            transaction_type: Some(U64::from(3)),
+            max_fee_per_blob_gas: Some(U128::from(5)),
+            blob_versioned_hashes: vec![B256::with_last_byte(1)],
+            ..Default::default()
+        };
+
+        let e: EthersTransaction = r.clone().try_into_ethers().unwrap();
+        assert_eq!(e.other.get("maxFeePerBlobGas"), Some(&json!("0x5")));
+
+        let roundtrip: Transaction = e.try_into_reth().unwrap();
+        assert_eq!(roundtrip.max_fee_per_blob_gas, r.max_fee_per_blob_gas);
+        assert_eq!(roundtrip.blob_versioned_hashes, r.blob_versioned_hashes);
+        assert!(roundtrip.other.is_empty());
+    }
+
+    #[test]
+    fn blob_transaction_receipt() {
+        let r = TransactionReceipt {
+            transaction_hash: Some(B256::with_last_byte(1)),
+            transaction_type: U8::from(3),
+            blob_gas_used: Some(U128::from(131_072)),
+            blob_gas_price: Some(U128::from(1)),
+            ..Default::default()
+        };
+
+        let e: EthersTransactionReceipt = r.clone().try_into_ethers().unwrap();
+        assert_eq!(e.other.get("blobGasUsed"), Some(&json!("0x20000")));
+        assert_eq!(r, e.try_into_reth().unwrap());
+    }
+
+    #[test]
+    fn blob_transaction_request() {
+        let e = Eip4844TransactionRequest {
+            tx: Eip1559TransactionRequest::new().to(EthersH160::from_low_u64_be(1)),
+            max_fee_per_blob_gas: Some(EthersU256::from(7)),
+            blob_versioned_hashes: vec![EthersH256::from_low_u64_be(2)],
+            sidecar: None,
+        };
+
+        let r: TransactionRequest = e.try_into_reth().unwrap();
+        assert_eq!(r.transaction_type, Some(U8::from(3)));
+        assert_eq!(r.max_fee_per_blob_gas, Some(U128::from(7)));
+        assert_eq!(r.blob_versioned_hashes, Some(vec![B256::with_last_byte(2)]));
+
+        let e = Eip4844TransactionRequest {
+            sidecar: Some(BlobSidecar {
+                blobs: vec![EthersBytes::from(vec![0; 3])],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let r: Result<TransactionRequest, _> = e.try_into_reth();
+        assert!(matches!(
+            r,
+            Err(ConversionError::InvalidLength { field: "blobs", expected: 131_072, len: 3 })
+        ));
+    }
+
+    #[test]
+    fn call_request() {
+        let tx = Eip1559TransactionRequest::new().to(EthersH160::from_low_u64_be(1));
+        let e = CallRequest::from(Eip4844TransactionRequest {
+            tx: tx.clone(),
+            max_fee_per_blob_gas: Some(EthersU256::from(7)),
+            blob_versioned_hashes: vec![EthersH256::from_low_u64_be(2)],
+            sidecar: None,
+        });
+
+        let r: TransactionRequest = e.try_into_reth().unwrap();
+        assert_eq!(r.transaction_type, Some(U8::from(3)));
+        assert_eq!(r.to, Some(Address::with_last_byte(1)));
+        assert_eq!(r.max_fee_per_blob_gas, Some(U128::from(7)));
+        assert_eq!(r.blob_versioned_hashes, Some(vec![B256::with_last_byte(2)]));
+
+        let r: TransactionRequest = CallRequest::from(tx).try_into_reth().unwrap();
+        assert_eq!(r.transaction_type, Some(U8::from(2)));
+        assert_eq!(r.blob_versioned_hashes, None);
+    }
+
+    #[test]
+    fn blob_transaction_request_roundtrip() {
+        let e = Eip4844TransactionRequest {
+            tx: Eip1559TransactionRequest::new()
+                .from(EthersH160::from_low_u64_be(1))
+                .to(EthersH160::from_low_u64_be(2))
+                .gas(100_000)
+                .value(3)
+                .data(EthersBytes::from(vec![4]))
+                .nonce(5)
+                .max_priority_fee_per_gas(6)
+                .max_fee_per_gas(7)
+                .chain_id(1u64),
+            max_fee_per_blob_gas: Some(EthersU256::from(8)),
+            blob_versioned_hashes: vec![EthersH256::from_low_u64_be(9)],
+            sidecar: Some(BlobSidecar {
+                blobs: vec![EthersBytes::from(vec![0; 131_072])],
+                commitments: vec![EthersBytes::from(vec![0; 48])],
+                proofs: vec![EthersBytes::from(vec![0; 48])],
+            }),
+        };
+
+        let r: TransactionRequest = e.clone().try_into_reth().unwrap();
+        assert_eq!(e, r.try_into_ethers().unwrap());
+
+        let r: Result<Eip4844TransactionRequest, _> =
+            TransactionRequest::default().try_into_ethers();
+        assert!(matches!(r, Err(ConversionError::MissingField("blob_versioned_hashes"))));
+    }
+
+    #[test]
+    fn blob_transaction_into_request() {
+        let mut e = EthersTransaction {
+            from: EthersH160::from_low_u64_be(1),
+            to: Some(EthersH160::from_low_u64_be(2)),
+            gas_price: Some(EthersU256::from(6)),
+            max_fee_per_gas: Some(EthersU256::from(7)),
+            max_priority_fee_per_gas: Some(EthersU256::from(1)),
+            transaction_type: Some(EthersU64::from(3)),
+            ..Default::default()
+        };
+        e.other.insert("maxFeePerBlobGas".to_string(), json!("0x8"));
+        e.other.insert("blobVersionedHashes".to_string(), json!([B256::with_last_byte(9)]));
+
+        let r: TransactionRequest = e.try_into_reth().unwrap();
+        assert_eq!(r.transaction_type, Some(U8::from(3)));
+        assert_eq!(r.gas_price, None);
+        assert_eq!(r.max_fee_per_blob_gas, Some(U128::from(8)));
+        assert_eq!(r.blob_versioned_hashes, Some(vec![B256::with_last_byte(9)]));
     }
 }
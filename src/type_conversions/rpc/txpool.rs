@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use crate::type_conversions::{ConversionError, ToEthers, TryToEthers};
+
+use ethers::types::{
+    Address as EthersAddress, TxpoolContent as EthersTxpoolContent,
+    TxpoolInspect as EthersTxpoolInspect, TxpoolInspectSummary as EthersTxpoolInspectSummary,
+    TxpoolStatus as EthersTxpoolStatus,
+};
+use reth_primitives::Address;
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus};
+
+/// Converts transactions grouped by sender and nonce, the nonces are kept as they are.
+fn grouped_into_ethers<T, U>(
+    grouped: BTreeMap<Address, BTreeMap<String, T>>,
+) -> BTreeMap<EthersAddress, BTreeMap<String, U>>
+where
+    T: ToEthers<U>,
+{
+    grouped
+        .into_iter()
+        .map(|(sender, txs)| {
+            let txs = txs.into_iter().map(|(nonce, tx)| (nonce, tx.into_ethers())).collect();
+            (sender.into_ethers(), txs)
+        })
+        .collect()
+}
+
+/// Like [`grouped_into_ethers`], for transactions whose conversion can fail.
+fn grouped_try_into_ethers<T, U>(
+    grouped: BTreeMap<Address, BTreeMap<String, T>>,
+) -> Result<BTreeMap<EthersAddress, BTreeMap<String, U>>, ConversionError>
+where
+    T: TryToEthers<U>,
+{
+    grouped
+        .into_iter()
+        .map(|(sender, txs)| {
+            let txs = txs
+                .into_iter()
+                .map(|(nonce, tx)| Ok((nonce, tx.try_into_ethers()?)))
+                .collect::<Result<_, ConversionError>>()?;
+            Ok((sender.into_ethers(), txs))
+        })
+        .collect()
+}
+
+/// TxpoolStatus (reth) -> (ethers)
+impl ToEthers<EthersTxpoolStatus> for TxpoolStatus {
+    fn into_ethers(self) -> EthersTxpoolStatus {
+        EthersTxpoolStatus {
+            pending: self.pending.into_ethers(),
+            queued: self.queued.into_ethers(),
+        }
+    }
+}
+
+/// TxpoolInspectSummary (reth) -> (ethers)
+impl ToEthers<EthersTxpoolInspectSummary> for TxpoolInspectSummary {
+    fn into_ethers(self) -> EthersTxpoolInspectSummary {
+        EthersTxpoolInspectSummary {
+            to: self.to.into_ethers(),
+            value: self.value.into_ethers(),
+            gas: self.gas.into_ethers(),
+            gas_price: self.gas_price.into_ethers(),
+        }
+    }
+}
+
+/// TxpoolInspect (reth) -> (ethers)
+impl ToEthers<EthersTxpoolInspect> for TxpoolInspect {
+    fn into_ethers(self) -> EthersTxpoolInspect {
+        EthersTxpoolInspect {
+            pending: grouped_into_ethers(self.pending),
+            queued: grouped_into_ethers(self.queued),
+        }
+    }
+}
+
+/// TxpoolContent (reth) -> (ethers)
+impl TryToEthers<EthersTxpoolContent> for TxpoolContent {
+    fn try_into_ethers(self) -> Result<EthersTxpoolContent, ConversionError> {
+        Ok(EthersTxpoolContent {
+            pending: grouped_try_into_ethers(self.pending)?,
+            queued: grouped_try_into_ethers(self.queued)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::type_conversions::ToEthers;
+
+    use ethers::types::{
+        Address as EthersAddress, TxpoolInspect as EthersTxpoolInspect,
+        TxpoolInspectSummary as EthersTxpoolInspectSummary, TxpoolStatus as EthersTxpoolStatus,
+        U256 as EthersU256, U64 as EthersU64,
+    };
+    use reth_primitives::{Address, U256, U64};
+    use reth_rpc_types::txpool::{TxpoolInspect, TxpoolInspectSummary, TxpoolStatus};
+
+    #[test]
+    fn txpool_status() {
+        let r = TxpoolStatus { pending: U64::from(3), queued: U64::from(1) };
+        let e = EthersTxpoolStatus { pending: EthersU64::from(3), queued: EthersU64::from(1) };
+
+        assert_eq!(e, r.into_ethers());
+    }
+
+    #[test]
+    fn txpool_inspect() {
+        let sender = Address::with_last_byte(1);
+        let summary = TxpoolInspectSummary {
+            to: Some(Address::with_last_byte(2)),
+            value: U256::from(1_000),
+            gas: U256::from(21_000),
+            gas_price: U256::from(7),
+        };
+        let r = TxpoolInspect {
+            pending: BTreeMap::from([(sender, BTreeMap::from([("4".to_string(), summary)]))]),
+            queued: BTreeMap::new(),
+        };
+
+        let e_summary = EthersTxpoolInspectSummary {
+            to: Some(EthersAddress::from_low_u64_be(2)),
+            value: EthersU256::from(1_000),
+            gas: EthersU256::from(21_000),
+            gas_price: EthersU256::from(7),
+        };
+        let e = EthersTxpoolInspect {
+            pending: BTreeMap::from([(
+                EthersAddress::from_low_u64_be(1),
+                BTreeMap::from([("4".to_string(), e_summary)]),
+            )]),
+            queued: BTreeMap::new(),
+        };
+
+        assert_eq!(e, r.into_ethers());
+    }
+}
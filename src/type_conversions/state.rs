@@ -0,0 +1,42 @@
+use super::ToEthers;
+
+use crate::state_diff::AccountInfo;
+
+use reth_primitives::{Account, KECCAK_EMPTY};
+
+/// Account (reth) -> AccountInfo (ethers)
+impl ToEthers<AccountInfo> for Account {
+    fn into_ethers(self) -> AccountInfo {
+        AccountInfo {
+            nonce: self.nonce,
+            balance: self.balance.into_ethers(),
+            code_hash: self.bytecode_hash.unwrap_or(KECCAK_EMPTY).into_ethers(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state_diff::AccountInfo, type_conversions::ToEthers};
+
+    use ethers::types::{H256 as EthersH256, U256 as EthersU256};
+    use reth_primitives::{Account, B256, KECCAK_EMPTY, U256};
+
+    #[test]
+    fn account() {
+        let account = Account { nonce: 1, balance: U256::from(2), bytecode_hash: None };
+        let info: AccountInfo = account.into_ethers();
+        assert_eq!(
+            AccountInfo {
+                nonce: 1,
+                balance: EthersU256::from(2),
+                code_hash: EthersH256::from(KECCAK_EMPTY.0)
+            },
+            info
+        );
+
+        let account = Account { bytecode_hash: Some(B256::with_last_byte(3)), ..account };
+        let info: AccountInfo = account.into_ethers();
+        assert_eq!(EthersH256::from_low_u64_be(3), info.code_hash);
+    }
+}
@@ -0,0 +1,358 @@
+//! Polling streams that mirror ethers' [`FilterWatcher`] without any RPC round trip.
+//!
+//! The watchers returned by [`Middleware::watch_blocks`] and friends poll the transport of the
+//! inner provider, which only reaches the database when it's a [`RethTransport`]. The watchers
+//! below work with any inner provider.
+//!
+//! [`RethTransport`]: crate::transport::RethTransport
+//!
+//! [`FilterWatcher`]: ethers::providers::FilterWatcher
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+    vec::IntoIter,
+};
+
+use ethers::{
+    providers::{FilterKind, Middleware, DEFAULT_LOCAL_POLL_INTERVAL},
+    types::{
+        Block as EthersBlock, Filter as EthersFilter, Log as EthersLog, H256 as EthersH256,
+        U256 as EthersU256,
+    },
+};
+use futures::{ready, Stream, StreamExt};
+use reth_primitives::BlockNumber;
+use reth_provider::{BlockHashReader, BlockNumReader, ProviderError};
+use reth_rpc_api::EthFilterApiServer;
+use reth_rpc_types::FilterChanges;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+use crate::{
+    type_conversions::{ToEthers, ToReth},
+    RethClient, RethFilter, RethMiddleware, RethMiddlewareError,
+};
+
+/// Streams the hashes of new canonical blocks by polling the best block number of the provider.
+#[must_use = "watchers do nothing unless you stream them"]
+pub struct BlockWatcher {
+    client: RethClient,
+    /// The last block that was yielded.
+    last: BlockNumber,
+    interval: Interval,
+    pending: IntoIter<EthersH256>,
+}
+
+impl BlockWatcher {
+    /// Creates a new watcher that yields the blocks after the current best block.
+    pub fn new(client: RethClient) -> Result<Self, ProviderError> {
+        let last = client.best_block_number()?;
+        Ok(Self {
+            client,
+            last,
+            interval: poll_interval(DEFAULT_LOCAL_POLL_INTERVAL),
+            pending: vec![].into_iter(),
+        })
+    }
+
+    /// Yields the blocks after `number` instead of the blocks after the current best block.
+    pub fn from_block(mut self, number: BlockNumber) -> Self {
+        self.last = number;
+        self
+    }
+
+    /// Sets the stream's polling interval
+    pub fn interval(mut self, duration: Duration) -> Self {
+        self.interval = poll_interval(duration);
+        self
+    }
+
+    /// Alias for Box::pin, mirroring [`FilterWatcher::stream`].
+    ///
+    /// [`FilterWatcher::stream`]: ethers::providers::FilterWatcher::stream
+    pub fn stream(self) -> Pin<Box<Self>> {
+        Box::pin(self)
+    }
+
+    fn new_block_hashes(&mut self) -> Result<Vec<EthersH256>, ProviderError> {
+        let best = self.client.best_block_number()?;
+        if best <= self.last {
+            return Ok(vec![])
+        }
+
+        let hashes = self.client.canonical_hashes_range(self.last + 1, best + 1)?;
+        self.last = best;
+        Ok(hashes.into_ethers())
+    }
+}
+
+impl Stream for BlockWatcher {
+    type Item = EthersH256;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let item @ Some(_) = self.pending.next() {
+                return Poll::Ready(item)
+            }
+
+            ready!(self.interval.poll_tick(cx));
+            // like `FilterWatcher`, errors yield no items and are retried on the next tick
+            self.pending = self.new_block_hashes().unwrap_or_default().into_iter();
+        }
+    }
+}
+
+/// A boxed request for the changes of a filter, which yields no items on errors.
+type FilterChangesFuture<'a, R> = Pin<Box<dyn Future<Output = Vec<R>> + Send + 'a>>;
+
+enum FilterWatcherState<'a, R> {
+    WaitForInterval,
+    GetFilterChanges(FilterChangesFuture<'a, R>),
+    NextItem(IntoIter<R>),
+}
+
+impl<'a, R> FilterWatcherState<'a, R> {
+    /// Polls the state machine shared by the filter watchers, requesting the changes of the filter
+    /// with `get_changes` on every tick of `interval`.
+    fn poll_next(
+        &mut self,
+        interval: &mut Interval,
+        cx: &mut Context<'_>,
+        get_changes: impl Fn() -> FilterChangesFuture<'a, R>,
+    ) -> Poll<Option<R>> {
+        loop {
+            *self = match self {
+                FilterWatcherState::WaitForInterval => {
+                    ready!(interval.poll_tick(cx));
+                    FilterWatcherState::GetFilterChanges(get_changes())
+                }
+                FilterWatcherState::GetFilterChanges(fut) => {
+                    let items = ready!(fut.as_mut().poll(cx));
+                    FilterWatcherState::NextItem(items.into_iter())
+                }
+                FilterWatcherState::NextItem(iter) => {
+                    if let item @ Some(_) = iter.next() {
+                        return Poll::Ready(item)
+                    }
+                    FilterWatcherState::WaitForInterval
+                }
+            };
+        }
+    }
+}
+
+/// Streams the changes of a filter installed in the embedded [`RethFilter`], the in-process
+/// counterpart of [`FilterWatcher`](ethers::providers::FilterWatcher).
+#[must_use = "filters do nothing unless you stream them"]
+pub struct RethFilterWatcher<R> {
+    /// The filter's installed id
+    pub id: EthersU256,
+    filter: RethFilter,
+    interval: Interval,
+    state: FilterWatcherState<'static, R>,
+}
+
+impl<R> RethFilterWatcher<R>
+where
+    R: DeserializeOwned + Send + 'static,
+{
+    /// Creates a new watcher for the filter with the given id.
+    pub fn new<T: Into<EthersU256>>(id: T, filter: RethFilter) -> Self {
+        Self {
+            id: id.into(),
+            filter,
+            interval: poll_interval(DEFAULT_LOCAL_POLL_INTERVAL),
+            state: FilterWatcherState::WaitForInterval,
+        }
+    }
+
+    /// Sets the stream's polling interval
+    pub fn interval(mut self, duration: Duration) -> Self {
+        self.interval = poll_interval(duration);
+        self
+    }
+
+    /// Alias for Box::pin, mirroring [`FilterWatcher::stream`].
+    ///
+    /// [`FilterWatcher::stream`]: ethers::providers::FilterWatcher::stream
+    pub fn stream(self) -> Pin<Box<Self>> {
+        Box::pin(self)
+    }
+}
+
+impl<R> Stream for RethFilterWatcher<R>
+where
+    R: DeserializeOwned + Send + Unpin + 'static,
+{
+    type Item = R;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let (filter, id) = (&this.filter, this.id.into_reth());
+
+        this.state.poll_next(&mut this.interval, cx, || {
+            let filter = filter.clone();
+            let id = id.clone();
+            // like `FilterWatcher`, errors yield no items and are retried on the next tick
+            Box::pin(async move {
+                match filter.filter_changes(id).await {
+                    Ok(changes) => filter_changes_into(changes).unwrap_or_default(),
+                    Err(_) => vec![],
+                }
+            })
+        })
+    }
+}
+
+/// Streams the changes of a filter through [`Middleware::get_filter_changes`] of any middleware
+/// stack, in place of the [`FilterWatcher`](ethers::providers::FilterWatcher) returned by
+/// [`Middleware::watch`].
+///
+/// `FilterWatcher` polls the provider at the bottom of the stack, which knows nothing about the
+/// filters of a [`RethMiddleware`]. This watcher goes through the whole stack instead, so code
+/// generic over [`Middleware`] can watch the filters of a stack containing a `RethMiddleware`.
+#[must_use = "filters do nothing unless you stream them"]
+pub struct MiddlewareFilterWatcher<'a, M, R> {
+    /// The filter's installed id
+    pub id: EthersU256,
+    client: &'a M,
+    interval: Interval,
+    state: FilterWatcherState<'a, R>,
+}
+
+impl<'a, M, R> MiddlewareFilterWatcher<'a, M, R>
+where
+    M: Middleware,
+{
+    /// Creates a new watcher for the filter with the given id, installed through `client`.
+    pub fn new<T: Into<EthersU256>>(id: T, client: &'a M) -> Self {
+        Self {
+            id: id.into(),
+            client,
+            interval: poll_interval(DEFAULT_LOCAL_POLL_INTERVAL),
+            state: FilterWatcherState::WaitForInterval,
+        }
+    }
+
+    /// Sets the stream's polling interval
+    pub fn interval(mut self, duration: Duration) -> Self {
+        self.interval = poll_interval(duration);
+        self
+    }
+
+    /// Alias for Box::pin, mirroring [`FilterWatcher::stream`].
+    ///
+    /// [`FilterWatcher::stream`]: ethers::providers::FilterWatcher::stream
+    pub fn stream(self) -> Pin<Box<Self>> {
+        Box::pin(self)
+    }
+}
+
+impl<'a, M> MiddlewareFilterWatcher<'a, M, EthersLog>
+where
+    M: Middleware,
+{
+    /// Installs a log filter through `client` and watches it, like [`Middleware::watch`].
+    pub async fn logs(client: &'a M, filter: &EthersFilter) -> Result<Self, M::Error> {
+        let id = client.new_filter(FilterKind::Logs(filter)).await?;
+        Ok(Self::new(id, client))
+    }
+}
+
+impl<'a, M> MiddlewareFilterWatcher<'a, M, EthersH256>
+where
+    M: Middleware,
+{
+    /// Installs a block filter through `client` and watches it, like
+    /// [`Middleware::watch_blocks`].
+    pub async fn blocks(client: &'a M) -> Result<Self, M::Error> {
+        let id = client.new_filter(FilterKind::NewBlocks).await?;
+        Ok(Self::new(id, client))
+    }
+
+    /// Installs a pending transaction filter through `client` and watches it, like
+    /// [`Middleware::watch_pending_transactions`].
+    pub async fn pending_transactions(client: &'a M) -> Result<Self, M::Error> {
+        let id = client.new_filter(FilterKind::PendingTransactions).await?;
+        Ok(Self::new(id, client))
+    }
+}
+
+impl<'a, M, R> Stream for MiddlewareFilterWatcher<'a, M, R>
+where
+    M: Middleware,
+    R: Serialize + DeserializeOwned + Send + Sync + Debug + Unpin + 'a,
+{
+    type Item = R;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let (client, id) = (this.client, this.id);
+
+        // like `FilterWatcher`, errors yield no items and are retried on the next tick
+        this.state.poll_next(&mut this.interval, cx, || {
+            Box::pin(async move { client.get_filter_changes(id).await.unwrap_or_default() })
+        })
+    }
+}
+
+/// Converts [`FilterChanges`] into the ethers type `R` through their shared JSON-RPC encoding,
+/// which is what [`Middleware::get_filter_changes`] is generic over.
+pub(crate) fn filter_changes_into<R: DeserializeOwned>(
+    changes: FilterChanges,
+) -> Result<Vec<R>, serde_json::Error> {
+    serde_json::from_value(serde_json::to_value(changes)?)
+}
+
+fn poll_interval(duration: Duration) -> Interval {
+    let mut interval = interval(duration);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+impl<M> RethMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Streams the hashes of new blocks straight from the database, the in-process counterpart of
+    /// [`Middleware::watch_blocks`].
+    ///
+    /// Blocks written by a running node are only picked up when the middleware follows it, see
+    /// [`RethMiddlewareBuilder::follow`](crate::builder::RethMiddlewareBuilder::follow).
+    pub fn watch_new_blocks(&self) -> Result<BlockWatcher, RethMiddlewareError<M>> {
+        Ok(BlockWatcher::new(self.reth_api.provider().clone())?)
+    }
+
+    /// Installs a log filter in the embedded [`RethFilter`] and streams its matches, the
+    /// in-process counterpart of [`Middleware::watch`].
+    pub async fn watch_logs(
+        &self,
+        filter: &EthersFilter,
+    ) -> Result<RethFilterWatcher<EthersLog>, RethMiddlewareError<M>> {
+        let id = self.new_filter(FilterKind::Logs(filter)).await?;
+        Ok(RethFilterWatcher::new(id, self.reth_filter.clone()))
+    }
+
+    /// Streams the hashes of transactions entering the embedded pool, the in-process counterpart
+    /// of [`Middleware::watch_pending_transactions`].
+    pub async fn watch_pool_transactions(
+        &self,
+    ) -> Result<RethFilterWatcher<EthersH256>, RethMiddlewareError<M>> {
+        let id = self.new_filter(FilterKind::PendingTransactions).await?;
+        Ok(RethFilterWatcher::new(id, self.reth_filter.clone()))
+    }
+
+    /// Streams new blocks straight from the database, the in-process counterpart of
+    /// [`Middleware::subscribe_blocks`] which needs no pubsub connection.
+    pub fn subscribe_new_blocks(
+        &self,
+    ) -> Result<impl Stream<Item = EthersBlock<EthersH256>> + '_, RethMiddlewareError<M>> {
+        Ok(self
+            .watch_new_blocks()?
+            .filter_map(move |hash| async move { self.get_block(hash).await.ok().flatten() }))
+    }
+}
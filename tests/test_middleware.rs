@@ -10,11 +10,16 @@ mod test_utils;
 // `reth node --chain goerli --datadir ./testdata --http --http.api all --debug.tip
 // 0xe9006d7148f879e1af79d12ba532d061e160ded8f9066c3d74c9724f65366d94`
 mod tests {
-    use std::path::{Path, PathBuf};
+    use std::{
+        future::IntoFuture,
+        path::{Path, PathBuf},
+        str::FromStr,
+        time::Duration,
+    };
 
     use ethers::{
         prelude::k256::ecdsa::SigningKey,
-        providers::Middleware,
+        providers::{spoof, FilterKind, Http, Middleware, MiddlewareError},
         signers::Wallet,
         types::{
             transaction::{
@@ -26,26 +31,41 @@ mod tests {
             Eip1559TransactionRequest, FeeHistory as EthersFeeHistory, Filter as EthersFilter,
             FilterBlockOption as EthersFilterBlockOption, GethTrace as EthersGethTrace,
             Log as EthersLog, NameOrAddress as EthersNameOrAddress, Trace as EthersTrace,
-            TraceType as EthersTraceType, Transaction as EthersTransaction,
-            TransactionReceipt as EthersTransactionReceipt,
+            TraceFilter as EthersTraceFilter, TraceType as EthersTraceType,
+            Transaction as EthersTransaction, TransactionReceipt as EthersTransactionReceipt,
             TransactionRequest as EthersTransactionRequest, TxHash as EthersTxHash,
             H256 as EthersH256, U256 as EthersU256,
         },
     };
 
-    use ethers_reth::type_conversions::ToReth;
+    use ethers_reth::{
+        bundle::CallBundle,
+        overrides::BlockOverrides,
+        pool::{PendingPoolTransaction, TxSubmission},
+        revert::RevertReason,
+        type_conversions::ToReth,
+        watcher::MiddlewareFilterWatcher,
+        RethMiddleware, RethMiddlewareError,
+    };
     use reth_primitives::{DEV, MAINNET, U64};
+    use reth_revm::{interpreter::Interpreter, Database, EvmContext, Inspector};
+    use reth_transaction_pool::TransactionPool;
 
+    use futures::{StreamExt, TryStreamExt};
     use serial_test::serial;
 
     use pretty_assertions::assert_eq;
 
-    use crate::test_utils::{spawn_reth_middleware, MAINNET_HTTP_URL};
+    use tokio::runtime::Handle;
+
+    use crate::test_utils::{spawn_http_provider, spawn_reth_middleware, MAINNET_HTTP_URL};
 
     const BLOCK_NUMBER: u64 = 3;
     const BLOCK_HASH: &str = "0x5ba8efbbe87e1f50f06bea90637e360bcf126e38d37d3d31bd0e5ed62d37fc7b";
 
     const WALLET_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    const WALLET_PRIVATE_KEY: &str =
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
     const WETH_ADDRESS: &str = "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512";
     const WETH_DEPLOY_TX_HASH: &str =
         "0x0b6fd363c5d835f29518d686a362972ac0fcb13552a5f95633354d4d0226b50f";
@@ -98,6 +118,459 @@ mod tests {
         assert_eq!(expected_call_result, call_result);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_call_with_overrides() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        let target = EthersAddress::from_low_u64_be(0x1234);
+
+        // SELFBALANCE TIMESTAMP ADD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let code: EthersBytes = "0x47420160005260206000f3".parse().unwrap();
+        let mut state = spoof::state();
+        state.account(target).balance(1_000.into()).code(code);
+        let block_overrides = BlockOverrides::default().timestamp(5);
+
+        let call_transaction =
+            EthersTypedTransaction::Eip1559(Eip1559TransactionRequest::new().to(target));
+        let call_result = reth_middleware
+            .call_with_overrides(
+                &call_transaction,
+                Some(block_id),
+                Some(state.clone()),
+                Some(block_overrides.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(EthersU256::from_big_endian(&call_result), EthersU256::from(1_005));
+
+        let gas = reth_middleware
+            .estimate_gas_with_overrides(&call_transaction, Some(block_id), Some(state.clone()))
+            .await
+            .unwrap();
+        assert!(gas > EthersU256::from(21_000));
+
+        let trace = reth_middleware
+            .trace_call_with_overrides(
+                call_transaction,
+                vec![EthersTraceType::Trace],
+                Some(BLOCK_NUMBER.into()),
+                Some(state),
+                Some(block_overrides),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            trace.output,
+            EthersBytes::from(EthersH256::from_low_u64_be(1_005).as_bytes().to_vec())
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_call_revert() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        let target = EthersAddress::from_low_u64_be(0x1234);
+
+        // reverts with `Panic(0x01)`
+        let code: EthersBytes = "0x634e487b7160e01b600052600160045260246000fd".parse().unwrap();
+        let mut state = spoof::state();
+        state.account(target).code(code);
+
+        let call_transaction =
+            EthersTypedTransaction::Eip1559(Eip1559TransactionRequest::new().to(target));
+        let err = reth_middleware
+            .call_with_overrides(&call_transaction, Some(block_id), Some(state), None)
+            .await
+            .unwrap_err();
+
+        let expected_data: EthersBytes =
+            "0x4e487b710000000000000000000000000000000000000000000000000000000000000001"
+                .parse()
+                .unwrap();
+        match &err {
+            RethMiddlewareError::Revert { data, reason, .. } => {
+                assert_eq!(&expected_data, data);
+                assert_eq!(&Some(RevertReason::Panic(1.into())), reason);
+            }
+            err => panic!("expected a revert, got {err:?}"),
+        }
+        assert_eq!("execution reverted: panic: assertion failed (0x1)", err.to_string());
+
+        let response = err.as_error_response().unwrap();
+        assert_eq!(Some(expected_data), response.as_revert_data());
+
+        // PUSH1 0 PUSH1 0 REVERT
+        let mut state = spoof::state();
+        state.account(target).code("0x60006000fd".parse().unwrap());
+        let err = reth_middleware
+            .call_with_overrides(&call_transaction, Some(block_id), Some(state), None)
+            .await
+            .unwrap_err();
+        match &err {
+            RethMiddlewareError::Revert { data, reason, .. } => {
+                assert!(data.is_empty());
+                assert_eq!(&None, reason);
+            }
+            err => panic!("expected a revert, got {err:?}"),
+        }
+        assert_eq!("execution reverted", err.to_string());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_simulate_bundle() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let to: EthersAddress = WETH_ADDRESS.parse().unwrap();
+
+        // transfer 0.1 WETH to 0x..1234, then query its balance
+        let transfer: EthersBytes =
+            "0xa9059cbb0000000000000000000000000000000000000000000000000000000000001234000000000000000000000000000000000000000000000000016345785d8a0000"
+                .parse()
+                .unwrap();
+        let balance_of: EthersBytes =
+            "0x70a082310000000000000000000000000000000000000000000000000000000000001234"
+                .parse()
+                .unwrap();
+        let txs: Vec<EthersTypedTransaction> = vec![
+            Eip1559TransactionRequest::new().from(from).to(to).data(transfer).into(),
+            Eip1559TransactionRequest::new().from(from).to(to).data(balance_of).into(),
+        ];
+        let expected_balance: EthersBytes =
+            "0x000000000000000000000000000000000000000000000000016345785d8a0000".parse().unwrap();
+
+        let outputs =
+            reth_middleware.call_many(txs.clone(), Some(block_id), None, None).await.unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[1], Ok(expected_balance.clone()));
+
+        let simulated =
+            reth_middleware.simulate_bundle(txs, Some(block_id), None, None).await.unwrap();
+        assert!(simulated.iter().all(|tx| tx.is_success()));
+        assert_eq!(simulated[0].logs.len(), 1);
+        assert_eq!(simulated[0].logs[0].address, to);
+        assert!(simulated[0].gas_used > EthersU256::zero());
+        assert_eq!(simulated[1].output, expected_balance);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_call_bundle() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let to: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        let nonce = reth_middleware.get_transaction_count(from, Some(block_id)).await.unwrap();
+        // transfer 0.1 WETH to 0x..1234
+        let transfer: EthersBytes =
+            "0xa9059cbb0000000000000000000000000000000000000000000000000000000000001234000000000000000000000000000000000000000000000000016345785d8a0000"
+                .parse()
+                .unwrap();
+        let transaction: EthersTypedTransaction = EthersTypedTransaction::Legacy(
+            EthersTransactionRequest::new()
+                .from(from)
+                .to(to)
+                .data(transfer)
+                .nonce(nonce)
+                .gas(100000)
+                .gas_price(100_000_000_000u64)
+                .chain_id(DEV.clone().chain().id()),
+        );
+        let wallet: Wallet<SigningKey> = WALLET_PRIVATE_KEY.parse().unwrap();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+
+        let bundle = CallBundle::new(vec![transaction.rlp_signed(&signature)], BLOCK_NUMBER + 1)
+            .state_block_number(BLOCK_NUMBER);
+        let response = reth_middleware.call_bundle(bundle).await.unwrap();
+
+        assert_eq!(BLOCK_NUMBER, response.state_block_number);
+        assert_eq!(1, response.results.len());
+
+        let result = &response.results[0];
+        assert_eq!(from, result.from_address);
+        assert_eq!(Some(to), result.to_address);
+        assert_eq!(EthersU256::from(100_000_000_000u64), result.gas_price);
+        assert_eq!(response.total_gas_used, result.gas_used);
+        assert_eq!(response.coinbase_diff, result.coinbase_diff);
+        assert!(result.revert.is_none());
+    }
+
+    /// Counts the executed opcodes.
+    #[derive(Default)]
+    struct OpcodeCounter {
+        steps: usize,
+    }
+
+    impl<DB: Database> Inspector<DB> for OpcodeCounter {
+        fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+            self.steps += 1;
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_inspect() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        let erc20_address: EthersNameOrAddress = WETH_ADDRESS.into();
+        let call_data: EthersBytes =
+            "0x70a08231000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+                .parse()
+                .unwrap();
+        let call_transaction = EthersTypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new().to(erc20_address).data(call_data),
+        );
+        let (counter, result) = reth_middleware
+            .inspect_call(&call_transaction, Some(block_id), OpcodeCounter::default())
+            .await
+            .unwrap();
+        assert!(result.result.is_success());
+        assert!(counter.steps > 0);
+
+        let transaction_hash: EthersH256 = WETH_DEPLOY_TX_HASH.parse().unwrap();
+        let (counter, result) = reth_middleware
+            .inspect_transaction(transaction_hash, OpcodeCounter::default())
+            .await
+            .unwrap();
+        assert!(result.result.is_success());
+        assert!(counter.steps > 0);
+
+        let block = reth_middleware.get_block(BLOCK_NUMBER).await.unwrap().unwrap();
+        let (_, results) =
+            reth_middleware.inspect_block(BLOCK_NUMBER, OpcodeCounter::default()).await.unwrap();
+        assert_eq!(block.transactions.len(), results.len());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_state_diff() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let wallet: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let diffs = reth_middleware.state_diff(1..=BLOCK_NUMBER, &[wallet]).unwrap();
+        assert!(!diffs.is_empty());
+
+        for diff in diffs {
+            assert_eq!(wallet, diff.address);
+            let account = diff.account.unwrap();
+            let before = reth_middleware
+                .get_balance(wallet, Some((diff.block_number - 1).into()))
+                .await
+                .unwrap();
+            let after =
+                reth_middleware.get_balance(wallet, Some(diff.block_number.into())).await.unwrap();
+            assert_eq!(before, account.before.map(|info| info.balance).unwrap_or_default());
+            assert_eq!(after, account.after.unwrap().balance);
+        }
+
+        let weth: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let diffs = reth_middleware.state_diff(1..=BLOCK_NUMBER, &[weth]).unwrap();
+        let deployment = diffs.iter().find_map(|diff| diff.account.as_ref()).unwrap();
+        assert_eq!(None, deployment.before);
+        assert!(diffs.iter().any(|diff| !diff.storage.is_empty()));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_history() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let wallet: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let history = reth_middleware.account_history_with_values(wallet).unwrap();
+        assert!(!history.is_empty());
+        assert_eq!(
+            history.iter().map(|(block_number, _)| *block_number).collect::<Vec<_>>(),
+            reth_middleware.account_history(wallet).unwrap()
+        );
+        for (block_number, account) in history {
+            let balance =
+                reth_middleware.get_balance(wallet, Some(block_number.into())).await.unwrap();
+            assert_eq!(balance, account.unwrap().balance);
+        }
+
+        let weth: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let diffs = reth_middleware.state_diff(1..=BLOCK_NUMBER, &[weth]).unwrap();
+        let (block_number, slot) = diffs
+            .iter()
+            .find_map(|diff| Some((diff.block_number, diff.storage.first()?.slot)))
+            .unwrap();
+        let history = reth_middleware.storage_history_with_values(weth, slot).unwrap();
+        assert!(history.iter().any(|(number, _)| *number == block_number));
+        for (block_number, value) in history {
+            let expected = reth_middleware
+                .get_storage_at(weth, slot, Some(block_number.into()))
+                .await
+                .unwrap();
+            assert_eq!(EthersU256::from_big_endian(expected.as_bytes()), value);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_storage_range() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let weth: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let dump: Vec<(EthersH256, EthersU256)> =
+            reth_middleware.dump_storage(weth, BLOCK_NUMBER).try_collect().await.unwrap();
+        assert!(!dump.is_empty());
+        for (slot, value) in &dump {
+            let expected = reth_middleware
+                .get_storage_at(weth, *slot, Some(BLOCK_NUMBER.into()))
+                .await
+                .unwrap();
+            assert_eq!(EthersU256::from_big_endian(expected.as_bytes()), *value);
+        }
+
+        // page through the storage one slot at a time
+        let mut slots = Vec::new();
+        let mut key_start = Some(EthersH256::zero());
+        while let Some(start) = key_start {
+            let range = reth_middleware
+                .get_storage_range(BLOCK_NUMBER, usize::MAX, weth, start, 1)
+                .await
+                .unwrap();
+            assert!(range.storage.len() <= 1);
+            for entry in range.storage.into_values() {
+                let value = EthersU256::from_big_endian(entry.value.as_bytes());
+                slots.push((entry.key.unwrap(), value));
+            }
+            key_start = range.next_key;
+        }
+        slots.sort();
+        assert_eq!(dump, slots);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fork() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let fork = reth_middleware.fork(Some(BLOCK_NUMBER.into())).await.unwrap();
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let weth: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let balance = fork.get_balance(from, None).await.unwrap();
+        let snapshot = fork.evm_snapshot().await;
+
+        // transfer 0.1 WETH to 0x..1234
+        let transfer: EthersBytes =
+            "0xa9059cbb0000000000000000000000000000000000000000000000000000000000001234000000000000000000000000000000000000000000000000016345785d8a0000"
+                .parse()
+                .unwrap();
+        let transaction = EthersTransactionRequest::new().from(from).to(weth).data(transfer);
+        let receipt = fork.mine_transaction(transaction).await.unwrap();
+
+        assert_eq!(Some(1.into()), receipt.status);
+        assert_eq!(Some((BLOCK_NUMBER + 1).into()), receipt.block_number);
+        assert_eq!(BLOCK_NUMBER + 1, fork.get_block_number().await.unwrap().as_u64());
+        assert_eq!(
+            Some(receipt.clone()),
+            fork.get_transaction_receipt(receipt.transaction_hash).await.unwrap()
+        );
+        assert!(fork.get_balance(from, None).await.unwrap() < balance);
+        // the database is untouched
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        assert_eq!(balance, reth_middleware.get_balance(from, Some(block_id)).await.unwrap());
+
+        let filter = EthersFilter::new().address(weth).from_block(BLOCK_NUMBER + 1);
+        assert_eq!(receipt.logs, fork.get_logs(&filter).await.unwrap());
+
+        let block = fork.get_block(EthersBlockNumber::Latest).await.unwrap().unwrap();
+        assert_eq!(receipt.block_hash, block.hash);
+        assert_eq!(vec![receipt.transaction_hash], block.transactions);
+        let block_hash = receipt.block_hash.unwrap();
+        assert_eq!(Some(block.clone()), fork.get_block(block_hash).await.unwrap());
+        assert_eq!(Some(block), fork.get_block(BLOCK_NUMBER + 1).await.unwrap());
+        assert!(fork.get_balance(from, Some(block_hash.into())).await.unwrap() < balance);
+
+        let later = fork.evm_snapshot().await;
+        assert!(fork.evm_revert(snapshot).await);
+        assert_eq!(balance, fork.get_balance(from, None).await.unwrap());
+        assert_eq!(BLOCK_NUMBER, fork.get_block_number().await.unwrap().as_u64());
+        assert_eq!(None, fork.get_transaction_receipt(receipt.transaction_hash).await.unwrap());
+        let block = fork.get_block(EthersBlockNumber::Latest).await.unwrap().unwrap();
+        assert_eq!(Some(BLOCK_NUMBER.into()), block.number);
+        assert_eq!(None, fork.get_block(block_hash).await.unwrap());
+        assert!(!fork.evm_revert(snapshot).await);
+        assert!(!fork.evm_revert(later).await);
+
+        // ids of reverted snapshots aren't handed out again
+        let next = fork.evm_snapshot().await;
+        assert!(next > later);
+        assert!(!fork.evm_revert(snapshot).await);
+        assert!(fork.evm_revert(next).await);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fork_cheats() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let fork = reth_middleware.fork(Some(BLOCK_NUMBER.into())).await.unwrap();
+        let target = EthersAddress::from_low_u64_be(0x1234);
+
+        fork.set_balance(target, EthersU256::exp10(18)).await.unwrap();
+        fork.set_nonce(target, 5).await.unwrap();
+        assert_eq!(EthersU256::exp10(18), fork.get_balance(target, None).await.unwrap());
+        assert_eq!(EthersU256::from(5), fork.get_transaction_count(target, None).await.unwrap());
+
+        // PUSH1 0 SLOAD TIMESTAMP ADD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let code: EthersBytes = "0x600054420160005260206000f3".parse().unwrap();
+        fork.set_code(target, code.clone()).await.unwrap();
+        fork.set_storage_at(target, EthersH256::zero(), EthersH256::from_low_u64_be(42))
+            .await
+            .unwrap();
+        fork.evm_set_next_block_timestamp(2_000_000_000).await.unwrap();
+        assert_eq!(code, fork.get_code(target, None).await.unwrap());
+
+        let call_transaction =
+            EthersTypedTransaction::Eip1559(Eip1559TransactionRequest::new().to(target));
+        let call_result = fork.call(&call_transaction, None).await.unwrap();
+        assert_eq!(EthersU256::from(2_000_000_042u64), EthersU256::from_big_endian(&call_result));
+
+        assert_eq!((BLOCK_NUMBER + 1).into(), fork.evm_mine().await);
+        assert_eq!(EthersU256::from(100), fork.evm_increase_time(100).await);
+        assert_eq!(EthersU256::from(120), fork.evm_increase_time(20).await);
+        assert!(fork.evm_set_next_block_timestamp(2_000_000_000).await.is_err());
+
+        // the offset applies to every later block
+        fork.evm_mine().await;
+        let block = fork.get_block(EthersBlockNumber::Latest).await.unwrap().unwrap();
+        assert_eq!(EthersU256::from(2_000_000_132u64), block.timestamp);
+        fork.evm_mine().await;
+        let block = fork.get_block(EthersBlockNumber::Latest).await.unwrap().unwrap();
+        assert_eq!(EthersU256::from(2_000_000_144u64), block.timestamp);
+
+        // the sender has code, so it needs to be impersonated
+        let transaction = EthersTransactionRequest::new().from(target).to(target).gas(100_000);
+        assert!(fork.mine_transaction(transaction.clone()).await.is_err());
+        fork.impersonate_account(target).await;
+        let receipt = fork.mine_transaction(transaction).await.unwrap();
+        assert_eq!(Some(1.into()), receipt.status);
+
+        // the database is untouched
+        let block_id: EthersBlockId = BLOCK_NUMBER.into();
+        assert!(reth_middleware.get_code(target, Some(block_id)).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_estimate_gas() {
@@ -267,6 +740,37 @@ mod tests {
         assert_eq!(expected_block_number, block_number.into_reth());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_builder() {
+        // the test database predates static files, a read-only middleware needs an empty directory
+        let static_files = tempfile::tempdir().unwrap();
+        let http_provider = spawn_http_provider(MAINNET_HTTP_URL).await.unwrap();
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .static_files_path(static_files.path())
+            .gas_cap(1_000_000)
+            .max_tracing_requests(1)
+            .max_cached_blocks(4)
+            .gas_oracle_blocks(2)
+            .build(http_provider, Handle::current())
+            .unwrap();
+
+        let block_number = reth_middleware.get_block_number().await.unwrap();
+        assert_eq!(U64::from(5), block_number.into_reth());
+
+        let block = reth_middleware.get_block(BLOCK_NUMBER).await.unwrap().unwrap();
+        assert_eq!(block.hash, Some(BLOCK_HASH.parse().unwrap()));
+
+        let tx = EthersTypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new()
+                .from(WALLET_ADDRESS.parse::<EthersAddress>().unwrap())
+                .to(EthersAddress::from_low_u64_be(0x1234)),
+        );
+        let gas = reth_middleware.estimate_gas(&tx, Some(BLOCK_NUMBER.into())).await.unwrap();
+        assert_eq!(gas, 21_000.into());
+    }
+
     // eth_getBlockReceipts is being deprecated on mainnet and is not available on goerli on
     // Alchemy // https://docs.alchemy.com/reference/eth-getblockreceipts
     #[tokio::test]
@@ -336,6 +840,112 @@ mod tests {
         assert_eq!(expected_transaction_count, transaction_count);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_send_raw_transaction_local() {
+        let http_provider = spawn_http_provider(MAINNET_HTTP_URL).await.unwrap();
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .tx_submission(TxSubmission::Local)
+            .build(http_provider, Handle::current())
+            .unwrap();
+
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let to: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let nonce = reth_middleware.get_transaction_count(from, None).await.unwrap();
+        let transaction: EthersTypedTransaction = EthersTypedTransaction::Legacy(
+            EthersTransactionRequest::new()
+                .from(from)
+                .to(to)
+                .nonce(nonce)
+                .gas(100000)
+                .gas_price(100_000_000_000u64)
+                .chain_id(DEV.clone().chain().id()),
+        );
+
+        let wallet: Wallet<SigningKey> = WALLET_PRIVATE_KEY.parse().unwrap();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+        let pending = reth_middleware
+            .send_raw_transaction(transaction.rlp_signed(&signature))
+            .await
+            .unwrap();
+
+        let pooled_transaction = reth_middleware.get_transaction(*pending).await.unwrap().unwrap();
+        assert_eq!(from, pooled_transaction.from);
+        assert_eq!(None, pooled_transaction.block_number);
+
+        let txpool_status = reth_middleware.txpool_status().await.unwrap();
+        assert_eq!(1, txpool_status.pending.as_u64());
+
+        let txpool_content = reth_middleware.txpool_content().await.unwrap();
+        let pending_transactions = txpool_content.pending.get(&from).unwrap();
+        let pending_transaction = pending_transactions.get(&nonce.to_string()).unwrap();
+        assert_eq!(*pending, pending_transaction.hash);
+
+        let txpool_inspect = reth_middleware.txpool_inspect().await.unwrap();
+        let summaries = txpool_inspect.pending.get(&from).unwrap();
+        assert_eq!(Some(to), summaries.get(&nonce.to_string()).unwrap().to);
+
+        // the pool rejects transactions of accounts without any balance
+        let wallet: Wallet<SigningKey> =
+            "0000000000000000000000000000000000000000000000000000000000000001".parse().unwrap();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+        assert!(reth_middleware
+            .send_raw_transaction(transaction.rlp_signed(&signature))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_send_transaction_local() {
+        let http_provider = spawn_http_provider(MAINNET_HTTP_URL).await.unwrap();
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .tx_submission(TxSubmission::Local)
+            .build(http_provider, Handle::current())
+            .unwrap();
+
+        // the embedded node has no accounts to sign with, and nothing reaches the inner provider
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let to = EthersAddress::from_low_u64_be(0x1234);
+        let request = EthersTransactionRequest::new().from(from).to(to);
+        let err = reth_middleware.send_transaction(request, None).await.unwrap_err();
+        assert!(err.as_inner().is_none());
+
+        // a transaction already in the database resolves right away
+        let deploy_hash: EthersTxHash = WETH_DEPLOY_TX_HASH.parse().unwrap();
+        let receipt =
+            PendingPoolTransaction::new(deploy_hash, &reth_middleware).await.unwrap().unwrap();
+        assert_eq!(deploy_hash, receipt.transaction_hash);
+
+        let nonce = reth_middleware.get_transaction_count(from, None).await.unwrap();
+        let transaction: EthersTypedTransaction = EthersTypedTransaction::Legacy(
+            EthersTransactionRequest::new()
+                .from(from)
+                .to(to)
+                .nonce(nonce)
+                .gas(21000)
+                .gas_price(100_000_000_000u64)
+                .chain_id(DEV.clone().chain().id()),
+        );
+        let wallet: Wallet<SigningKey> = WALLET_PRIVATE_KEY.parse().unwrap();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+        let hash = *reth_middleware
+            .send_local_transaction(transaction.rlp_signed(&signature))
+            .await
+            .unwrap();
+        let pending = || {
+            PendingPoolTransaction::new(hash, &reth_middleware).interval(Duration::from_millis(10))
+        };
+
+        // nothing mines the transaction, so it stays pending until it leaves the pool
+        let timeout = Duration::from_millis(100);
+        assert!(tokio::time::timeout(timeout, pending().into_future()).await.is_err());
+        reth_middleware.reth_api().pool().remove_transactions(vec![hash.into_reth()]);
+        assert_eq!(None, pending().await.unwrap());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_block() {
@@ -389,6 +999,206 @@ mod tests {
         assert_eq!(expected_logs, logs);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_scan_logs() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let address: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let filter =
+            EthersFilter::new().from_block(0).to_block(BLOCK_NUMBER).address(vec![address]);
+
+        let expected_logs = reth_middleware.get_logs(&filter).await.unwrap();
+
+        let logs: Vec<EthersLog> = reth_middleware
+            .scan_logs(&filter)
+            .chunk_size(1)
+            .concurrency(2)
+            .stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(expected_logs, logs);
+
+        let logs: Vec<EthersLog> =
+            reth_middleware.scan_logs_paginated(&filter, 2).try_collect().await.unwrap();
+        assert_eq!(expected_logs, logs);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_scan_logs_max_blocks_per_filter() {
+        let http_provider = spawn_http_provider(MAINNET_HTTP_URL).await.unwrap();
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .max_blocks_per_filter(1)
+            .build(http_provider, Handle::current())
+            .unwrap();
+
+        let address: EthersAddress = WETH_ADDRESS.parse().unwrap();
+        let filter =
+            EthersFilter::new().from_block(0).to_block(BLOCK_NUMBER).address(vec![address]);
+        assert!(reth_middleware.get_logs(&filter).await.is_err());
+
+        // chunk sizes above the limit of the embedded filter are clamped instead of rejected
+        let expected_logs: Vec<EthersLog> =
+            reth_middleware.scan_logs(&filter).chunk_size(1).stream().try_collect().await.unwrap();
+        assert!(!expected_logs.is_empty());
+
+        let logs: Vec<EthersLog> =
+            reth_middleware.scan_logs(&filter).stream().try_collect().await.unwrap();
+        assert_eq!(expected_logs, logs);
+
+        let logs: Vec<EthersLog> =
+            reth_middleware.scan_logs_paginated(&filter, u64::MAX).try_collect().await.unwrap();
+        assert_eq!(expected_logs, logs);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_filter_lifecycle() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let filter_id = reth_middleware.new_filter(FilterKind::NewBlocks).await.unwrap();
+
+        // the database does not change, so there are no new blocks
+        let changes: Vec<EthersH256> = reth_middleware.get_filter_changes(filter_id).await.unwrap();
+        assert_eq!(Vec::<EthersH256>::new(), changes);
+
+        assert!(reth_middleware.uninstall_filter(filter_id).await.unwrap());
+        assert!(!reth_middleware.uninstall_filter(filter_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_middleware_filter_watcher() {
+        let http_provider = spawn_http_provider(MAINNET_HTTP_URL).await.unwrap();
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .tx_submission(TxSubmission::Local)
+            .build(http_provider, Handle::current())
+            .unwrap();
+
+        // the filters are installed in and polled from the embedded `EthFilter`
+        let filter = EthersFilter::new().address(WETH_ADDRESS.parse::<EthersAddress>().unwrap());
+        let logs = MiddlewareFilterWatcher::logs(&reth_middleware, &filter).await.unwrap();
+        assert!(reth_middleware.uninstall_filter(logs.id).await.unwrap());
+
+        let mut pending = MiddlewareFilterWatcher::pending_transactions(&reth_middleware)
+            .await
+            .unwrap()
+            .interval(Duration::from_millis(10));
+
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let nonce = reth_middleware.get_transaction_count(from, None).await.unwrap();
+        let transaction: EthersTypedTransaction = EthersTypedTransaction::Legacy(
+            EthersTransactionRequest::new()
+                .from(from)
+                .to(EthersAddress::from_low_u64_be(0x1234))
+                .nonce(nonce)
+                .gas(21000)
+                .gas_price(100_000_000_000u64)
+                .chain_id(DEV.clone().chain().id()),
+        );
+        let wallet: Wallet<SigningKey> = WALLET_PRIVATE_KEY.parse().unwrap();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+        let hash =
+            *reth_middleware.send_raw_transaction(transaction.rlp_signed(&signature)).await.unwrap();
+
+        let next = tokio::time::timeout(Duration::from_secs(5), pending.next()).await.unwrap();
+        assert_eq!(Some(hash), next);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_watch_new_blocks() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let hashes: Vec<EthersH256> = reth_middleware
+            .watch_new_blocks()
+            .unwrap()
+            .from_block(BLOCK_NUMBER)
+            .interval(Duration::from_millis(10))
+            .take(2)
+            .collect()
+            .await;
+        let block = reth_middleware.get_block(BLOCK_NUMBER + 2).await.unwrap().unwrap();
+        assert_eq!(block.parent_hash, hashes[0]);
+        assert_eq!(block.hash, Some(hashes[1]));
+
+        // without `follow`, the best block never moves past the tip the database was opened at
+        let mut watcher =
+            reth_middleware.watch_new_blocks().unwrap().interval(Duration::from_millis(10));
+        assert!(tokio::time::timeout(Duration::from_millis(100), watcher.next()).await.is_err());
+
+        let mut blocks = Box::pin(reth_middleware.subscribe_new_blocks().unwrap());
+        assert!(tokio::time::timeout(Duration::from_millis(100), blocks.next()).await.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_watch_blocks_with_transport() {
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .build_with_transport(Http::from_str(MAINNET_HTTP_URL).unwrap(), Handle::current())
+            .unwrap();
+
+        // the watcher polls the filter of the embedded node through the transport of the provider
+        let watcher = reth_middleware.watch_blocks().await.unwrap();
+        let changes: Vec<EthersH256> =
+            reth_middleware.provider().get_filter_changes(watcher.id).await.unwrap();
+        assert!(changes.is_empty());
+        assert!(reth_middleware.uninstall_filter(watcher.id).await.unwrap());
+
+        // without `follow`, the best block never moves past the tip the database was opened at
+        let mut watcher =
+            reth_middleware.watch_blocks().await.unwrap().interval(Duration::from_millis(10));
+        assert!(tokio::time::timeout(Duration::from_millis(100), watcher.next()).await.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_watch_with_transport() {
+        let reth_middleware = RethMiddleware::builder(get_db_dir())
+            .chain_spec(DEV.clone())
+            .tx_submission(TxSubmission::Local)
+            .build_with_transport(Http::from_str(MAINNET_HTTP_URL).unwrap(), Handle::current())
+            .unwrap();
+
+        let filter = EthersFilter::new().address(WETH_ADDRESS.parse::<EthersAddress>().unwrap());
+        let logs = reth_middleware.watch(&filter).await.unwrap();
+        assert!(reth_middleware.uninstall_filter(logs.id).await.unwrap());
+
+        let mut pending = reth_middleware
+            .watch_pending_transactions()
+            .await
+            .unwrap()
+            .interval(Duration::from_millis(10));
+
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let nonce = reth_middleware.get_transaction_count(from, None).await.unwrap();
+        let transaction: EthersTypedTransaction = EthersTypedTransaction::Legacy(
+            EthersTransactionRequest::new()
+                .from(from)
+                .to(EthersAddress::from_low_u64_be(0x1234))
+                .nonce(nonce)
+                .gas(21000)
+                .gas_price(100_000_000_000u64)
+                .chain_id(DEV.clone().chain().id()),
+        );
+        let wallet: Wallet<SigningKey> = WALLET_PRIVATE_KEY.parse().unwrap();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+        let hash =
+            *reth_middleware.send_raw_transaction(transaction.rlp_signed(&signature)).await.unwrap();
+
+        // the transaction only reached the embedded pool, which the watcher polls
+        let next = tokio::time::timeout(Duration::from_secs(5), pending.next()).await.unwrap();
+        assert_eq!(Some(hash), next);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_trace_call() {
@@ -561,6 +1371,12 @@ mod tests {
             expected_trace_replay_block_transactions,
             trace_replay_block_transactions_result
         );
+
+        let unknown_block: EthersBlockNumber = 1_000_000.into();
+        let result = reth_middleware
+            .trace_replay_block_transactions(unknown_block, vec![EthersTraceType::Trace])
+            .await;
+        assert!(result.is_err());
     }
 
     #[tokio::test]
@@ -618,6 +1434,56 @@ mod tests {
         assert_eq!(expected_trace_transaction, trace_transaction_result);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_trace_filter() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let from: EthersAddress = WALLET_ADDRESS.parse().unwrap();
+        let transaction_hash: EthersH256 = WETH_DEPLOY_TX_HASH.parse().unwrap();
+        let filter = EthersTraceFilter::default()
+            .from_block(0)
+            .to_block(BLOCK_NUMBER)
+            .from_address(vec![from]);
+
+        let traces = reth_middleware.trace_filter(filter.clone()).await.unwrap();
+        assert!(traces.iter().all(|trace| trace.block_number <= BLOCK_NUMBER));
+
+        let deploy_traces: Vec<EthersTrace> = traces
+            .iter()
+            .filter(|trace| trace.transaction_hash == Some(transaction_hash))
+            .cloned()
+            .collect();
+        let expected_deploy_traces =
+            reth_middleware.trace_transaction(transaction_hash).await.unwrap();
+        assert_eq!(expected_deploy_traces, deploy_traces);
+
+        // the result doesn't depend on how the range is split
+        let scanned: Vec<EthersTrace> = reth_middleware
+            .scan_traces(&filter)
+            .chunk_size(1)
+            .concurrency(2)
+            .stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(traces, scanned);
+
+        // chunk sizes above reth's trace_filter limit are clamped instead of rejected
+        let scanned: Vec<EthersTrace> = reth_middleware
+            .scan_traces(&filter)
+            .chunk_size(u64::MAX)
+            .stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(traces, scanned);
+
+        let page = reth_middleware.trace_filter(filter.after(1).count(1)).await.unwrap();
+        assert_eq!(traces.iter().skip(1).take(1).cloned().collect::<Vec<_>>(), page);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_debug_trace_transaction() {
@@ -663,6 +1529,38 @@ mod tests {
         assert_eq!(expected_debug_trace_block_by_hash, debug_trace_block_by_hash_result);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_debug_trace_block_detailed() {
+        let reth_middleware =
+            spawn_reth_middleware(MAINNET_HTTP_URL, DEV.clone(), get_db_dir()).await;
+
+        let block_hash: EthersH256 = BLOCK_HASH.parse().unwrap();
+
+        let debug_trace_block_detailed_result = reth_middleware
+            .debug_trace_block_detailed(block_hash, Default::default())
+            .await
+            .unwrap();
+
+        let block = reth_middleware.get_block(block_hash).await.unwrap().unwrap();
+        let tx_hashes: Vec<Option<EthersTxHash>> =
+            debug_trace_block_detailed_result.iter().map(|trace| trace.tx_hash).collect();
+        assert_eq!(block.transactions.into_iter().map(Some).collect::<Vec<_>>(), tx_hashes);
+
+        let expected_debug_trace_block_by_hash_path =
+            get_testdata_dir().join("expected_debug_trace_block_by_hash.json");
+        let expected_debug_trace_block_by_hash: Vec<EthersGethTrace> = serde_json::from_str(
+            &std::fs::read_to_string(expected_debug_trace_block_by_hash_path).unwrap(),
+        )
+        .unwrap();
+        let traces: Vec<EthersGethTrace> = debug_trace_block_detailed_result
+            .into_iter()
+            .map(|trace| trace.result.unwrap())
+            .collect();
+
+        assert_eq!(expected_debug_trace_block_by_hash, traces);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_debug_trace_block_by_number() {